@@ -1,5 +1,9 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::io::{self, IsTerminal};
+use std::sync::Arc;
 
+use crate::constants;
 use crate::credentials;
 use crate::error;
 use crate::models;
@@ -11,17 +15,23 @@ use crate::models::TimeEntry;
 use crate::models::Workspace;
 use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine as _};
+use chrono::{Duration, NaiveDate, Utc};
+use colored::Colorize;
 use error::ApiError;
 #[cfg(test)]
 use mockall::automock;
 use models::{ResultWithDefaultError, User};
 use reqwest::Client;
-use reqwest::{header, RequestBuilder};
+use reqwest::{header, Method, RequestBuilder, Response, StatusCode};
 use serde::{de, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
 use super::models::NetworkClient;
+use super::models::NetworkCreateClient;
 use super::models::NetworkCreateProject;
 use super::models::NetworkCreateTag;
+use super::models::NetworkCreateTask;
 use super::models::NetworkProject;
 use super::models::NetworkRenameProject;
 use super::models::NetworkRenameTag;
@@ -43,6 +53,8 @@ pub trait ApiClient {
         since: Option<String>,
         until: Option<String>,
     ) -> ResultWithDefaultError<Vec<TimeEntry>>;
+    /// Fetches one entry by id, however old it is.
+    async fn get_time_entry(&self, id: i64) -> ResultWithDefaultError<TimeEntry>;
     async fn delete_time_entry(
         &self,
         workspace_id: i64,
@@ -54,6 +66,7 @@ pub trait ApiClient {
         workspace_id: i64,
         name: String,
         color: String,
+        client_id: Option<i64>,
     ) -> ResultWithDefaultError<Project>;
 
     async fn delete_project(
@@ -69,6 +82,28 @@ pub trait ApiClient {
         new_name: String,
     ) -> ResultWithDefaultError<Project>;
 
+    async fn create_client(
+        &self,
+        workspace_id: i64,
+        name: String,
+    ) -> ResultWithDefaultError<crate::models::Client>;
+
+    async fn delete_client(&self, workspace_id: i64, client_id: i64) -> ResultWithDefaultError<()>;
+
+    async fn create_task(
+        &self,
+        workspace_id: i64,
+        project: Project,
+        name: String,
+    ) -> ResultWithDefaultError<Task>;
+
+    async fn delete_task(
+        &self,
+        workspace_id: i64,
+        project_id: i64,
+        task_id: i64,
+    ) -> ResultWithDefaultError<()>;
+
     async fn get_tags(&self, workspace_id: i64) -> ResultWithDefaultError<Vec<Tag>>;
 
     async fn create_tag(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<Tag>;
@@ -86,6 +121,7 @@ pub trait ApiClient {
 pub struct V9ApiClient {
     http_client: Client,
     base_url: String,
+    dry_run: bool,
 }
 
 impl V9ApiClient {
@@ -94,6 +130,152 @@ impl V9ApiClient {
         since: Option<&str>,
         until: Option<&str>,
     ) -> ResultWithDefaultError<Vec<NetworkTimeEntry>> {
+        let windows = match (since.and_then(parse_date), until) {
+            (Some(since), None) => date_windows(since, Utc::now().date_naive() + Duration::days(1)),
+            (Some(since), Some(until)) => match parse_date(until) {
+                Some(until) => date_windows(since, until),
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        if windows.len() <= 1 {
+            return self
+                .get_list::<NetworkTimeEntry>(self.time_entries_url(since, until))
+                .await;
+        }
+
+        // The API caps how much history a single request returns, so long ranges
+        // are split into windows that are fetched a few at a time.
+        let semaphore = Arc::new(Semaphore::new(constants::MAX_CONCURRENT_REQUESTS));
+        let mut requests = JoinSet::new();
+        for (window_start, window_end) in windows.iter() {
+            let url = self.time_entries_url(
+                Some(&window_start.to_string()),
+                Some(&window_end.to_string()),
+            );
+            let request = self.http_client.get(url);
+            let semaphore = semaphore.clone();
+            requests.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                V9ApiClient::send::<Option<Vec<NetworkTimeEntry>>>(request)
+                    .await
+                    .map(Option::unwrap_or_default)
+            });
+        }
+
+        let show_progress =
+            windows.len() > constants::PROGRESS_WINDOW_THRESHOLD && io::stderr().is_terminal();
+        let mut fetched_windows = 0;
+        let mut entries: HashMap<i64, NetworkTimeEntry> = HashMap::new();
+        while let Some(result) = requests.join_next().await {
+            let window_entries = result.map_err(|_| -> Box<dyn std::error::Error + Send> {
+                Box::new(ApiError::Network)
+            })??;
+            entries.extend(window_entries.into_iter().map(|te| (te.id, te)));
+            fetched_windows += 1;
+            if show_progress {
+                eprint!(
+                    "\rFetching time entries {}/{}",
+                    fetched_windows,
+                    windows.len()
+                );
+            }
+        }
+        if show_progress {
+            eprintln!();
+        }
+
+        let mut entries: Vec<NetworkTimeEntry> = entries.into_values().collect();
+        entries.sort_by_key(|te| std::cmp::Reverse(te.start));
+        Ok(entries)
+    }
+
+    /// Resolves the projects and tasks of the entries `network_entries`
+    /// fetches, looking them up at the same time.
+    async fn with_projects_and_tasks(
+        &self,
+        network_entries: impl Future<Output = ResultWithDefaultError<Vec<NetworkTimeEntry>>>,
+    ) -> ResultWithDefaultError<Vec<TimeEntry>> {
+        let (network_entries, network_projects, network_tasks, network_clients) = tokio::join!(
+            network_entries,
+            self.get_projects(),
+            self.get_tasks(),
+            self.get_clients(),
+        );
+
+        let clients: HashMap<i64, crate::models::Client> = network_clients?
+            .into_iter()
+            .map(|c| {
+                (
+                    c.id,
+                    crate::models::Client {
+                        id: c.id,
+                        name: c.name,
+                        workspace_id: c.wid,
+                    },
+                )
+            })
+            .collect();
+
+        let projects: HashMap<i64, Project> = network_projects?
+            .into_iter()
+            .map(|p| {
+                (
+                    p.id,
+                    Project {
+                        id: p.id,
+                        name: p.name.clone(),
+                        workspace_id: p.workspace_id,
+                        client: clients.get(&p.client_id.unwrap_or(-1)).cloned(),
+                        is_private: p.is_private,
+                        active: p.active,
+                        at: p.at,
+                        created_at: p.created_at,
+                        color: p.color,
+                        billable: p.billable,
+                    },
+                )
+            })
+            .collect();
+
+        let tasks: HashMap<i64, Task> = network_tasks?
+            .into_iter()
+            .filter_map(|t| {
+                projects.get(&t.project_id).map(|project| {
+                    (
+                        t.id,
+                        Task {
+                            id: t.id,
+                            name: t.name,
+                            project: project.clone(),
+                            workspace_id: t.workspace_id,
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        let entries = network_entries?
+            .into_iter()
+            .map(|te| TimeEntry {
+                id: te.id,
+                description: te.description,
+                start: te.start,
+                stop: te.stop,
+                duration: te.duration,
+                billable: te.billable,
+                workspace_id: te.workspace_id,
+                tags: te.tags.unwrap_or_default(),
+                project: projects.get(&te.project_id.unwrap_or(-1)).cloned(),
+                task: tasks.get(&te.task_id.unwrap_or(-1)).cloned(),
+                ..Default::default()
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    fn time_entries_url(&self, since: Option<&str>, until: Option<&str>) -> String {
         let mut url = format!("{}/me/time_entries", self.base_url);
         let mut params: Vec<String> = Vec::new();
         if let Some(since) = since {
@@ -105,27 +287,27 @@ impl V9ApiClient {
         if !params.is_empty() {
             url = format!("{}?{}", url, params.join("&"));
         }
-        self.get::<Vec<NetworkTimeEntry>>(url).await
+        url
     }
 
     async fn get_projects(&self) -> ResultWithDefaultError<Vec<NetworkProject>> {
         let url = format!("{}/me/projects", self.base_url);
-        self.get::<Vec<NetworkProject>>(url).await
+        self.get_list::<NetworkProject>(url).await
     }
 
     async fn get_clients(&self) -> ResultWithDefaultError<Vec<NetworkClient>> {
         let url = format!("{}/me/clients", self.base_url);
-        self.get::<Vec<NetworkClient>>(url).await
+        self.get_list::<NetworkClient>(url).await
     }
 
     async fn get_tasks(&self) -> ResultWithDefaultError<Vec<NetworkTask>> {
         let url = format!("{}/me/tasks", self.base_url);
-        self.get::<Vec<NetworkTask>>(url).await
+        self.get_list::<NetworkTask>(url).await
     }
 
     async fn get_workspaces(&self) -> ResultWithDefaultError<Vec<NetworkWorkspace>> {
         let url = format!("{}/me/workspaces", self.base_url);
-        self.get::<Vec<NetworkWorkspace>>(url).await
+        self.get_list::<NetworkWorkspace>(url).await
     }
 
     async fn get_workspace_tags(
@@ -133,7 +315,7 @@ impl V9ApiClient {
         workspace_id: i64,
     ) -> ResultWithDefaultError<Vec<NetworkTag>> {
         let url = format!("{}/workspaces/{}/tags", self.base_url, workspace_id);
-        self.get::<Vec<NetworkTag>>(url).await
+        self.get_list::<NetworkTag>(url).await
     }
 
     pub fn from_credentials(
@@ -161,14 +343,45 @@ impl V9ApiClient {
         let api_client = Self {
             http_client,
             base_url: "https://track.toggl.com/api/v9".to_string(),
+            dry_run: false,
         };
         Ok(api_client)
     }
 
+    /// In dry-run mode lookups still hit the API, but every request that
+    /// would change something is printed to stderr instead of sent and the
+    /// caller gets a result built from the request (new entities get id 0).
+    pub fn with_dry_run(self, dry_run: bool) -> V9ApiClient {
+        V9ApiClient { dry_run, ..self }
+    }
+
+    fn skip_request<Body: Serialize>(&self, method: &str, url: &str, body: Option<&Body>) -> bool {
+        if self.dry_run {
+            let body = body
+                .map(|body| {
+                    " ".to_string()
+                        + &serde_json::to_string(body).expect("failed to serialize to JSON")
+                })
+                .unwrap_or_default();
+            eprintln!("{} {method} {url}{body}", "Would send:".yellow());
+        }
+        self.dry_run
+    }
+
     async fn get<T: de::DeserializeOwned>(&self, url: String) -> ResultWithDefaultError<T> {
         V9ApiClient::send::<T>(self.http_client.get(url)).await
     }
 
+    /// Some endpoints answer `null` instead of an empty list.
+    async fn get_list<T: de::DeserializeOwned>(
+        &self,
+        url: String,
+    ) -> ResultWithDefaultError<Vec<T>> {
+        self.get::<Option<Vec<T>>>(url)
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
     async fn put<T: de::DeserializeOwned, Body: Serialize>(
         &self,
         url: String,
@@ -186,48 +399,113 @@ impl V9ApiClient {
     }
 
     async fn send<T: de::DeserializeOwned>(request: RequestBuilder) -> ResultWithDefaultError<T> {
-        match request.send().await {
-            Err(_) => Err(Box::new(ApiError::Network)),
-            Ok(response) => match response.json::<T>().await {
-                Err(_) => Err(Box::new(ApiError::Deserialization)),
-                Ok(parsed_response) => Ok(parsed_response),
-            },
+        let response = V9ApiClient::send_with_retries(request).await?;
+        match response.json::<T>().await {
+            Err(_) => Err(Box::new(ApiError::Deserialization)),
+            Ok(parsed_response) => Ok(parsed_response),
         }
     }
 
-    async fn delete(&self, url: String) -> ResultWithDefaultError<()> {
-        match self.http_client.delete(url).send().await {
-            Err(_) => Err(Box::new(ApiError::Network)),
-            Ok(response) => {
-                if response.status().is_success() {
-                    Ok(())
-                } else {
-                    Err(Box::new(ApiError::Deserialization))
+    /// Sends `request`, waiting and sending it again while Toggl is rate
+    /// limiting (429) or failing (5xx). A rate-limited request wasn't handled
+    /// at all, but only reads are repeated after a server error, as a write
+    /// may have gone through. Any other status outside 2xx is an error.
+    async fn send_with_retries(request: RequestBuilder) -> ResultWithDefaultError<Response> {
+        let is_read = request
+            .try_clone()
+            .and_then(|clone| clone.build().ok())
+            .is_some_and(|built| built.method() == Method::GET);
+        let mut request = request;
+        let mut attempt = 0;
+        loop {
+            let retry = request.try_clone();
+            let response =
+                request
+                    .send()
+                    .await
+                    .map_err(|_| -> Box<dyn std::error::Error + Send> {
+                        Box::new(ApiError::Network)
+                    })?;
+            let status = response.status();
+            let retryable =
+                status == StatusCode::TOO_MANY_REQUESTS || (status.is_server_error() && is_read);
+            match retry {
+                Some(retry) if retryable && attempt < constants::MAX_REQUEST_RETRIES => {
+                    let retry_after = response
+                        .headers()
+                        .get(header::RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok());
+                    tokio::time::sleep(retry_delay(retry_after, attempt)).await;
+                    attempt += 1;
+                    request = retry;
                 }
+                _ => return check_status(response),
             }
         }
     }
+
+    async fn delete(&self, url: String) -> ResultWithDefaultError<()> {
+        V9ApiClient::send_with_retries(self.http_client.delete(url))
+            .await
+            .map(|_| ())
+    }
+}
+
+fn check_status(response: Response) -> ResultWithDefaultError<Response> {
+    let error = match response.status() {
+        status if status.is_success() => return Ok(response),
+        StatusCode::UNAUTHORIZED => ApiError::Unauthorized,
+        StatusCode::FORBIDDEN => ApiError::Forbidden,
+        StatusCode::NOT_FOUND => ApiError::NotFound,
+        status => ApiError::Status(status.as_u16()),
+    };
+    Err(Box::new(error))
+}
+
+/// How long to wait before retry number `attempt + 1`: what `Retry-After`
+/// asks for in seconds, or a delay doubling with every attempt.
+fn retry_delay(retry_after: Option<&str>, attempt: u32) -> std::time::Duration {
+    let seconds = retry_after
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(constants::RETRY_BASE_DELAY_SECONDS << attempt);
+    std::time::Duration::from_secs(seconds)
 }
 
 #[async_trait]
 impl ApiClient for V9ApiClient {
     async fn get_user(&self) -> ResultWithDefaultError<User> {
         let url = format!("{}/me", self.base_url);
-        return self.get::<User>(url).await;
+        // Toggl answers a rejected token on /me with 403, anywhere else 403
+        // means the account lacks permission for the item
+        match self.get::<User>(url).await {
+            Err(error) if matches!(error.downcast_ref::<ApiError>(), Some(ApiError::Forbidden)) => {
+                Err(Box::new(ApiError::Unauthorized))
+            }
+            result => result,
+        }
     }
 
     async fn create_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64> {
         let url = format!("{}/time_entries", self.base_url);
+        let body: NetworkTimeEntry = time_entry.into();
+        if self.skip_request("POST", &url, Some(&body)) {
+            return Ok(0);
+        }
         let network_time_entry = self
-            .post::<NetworkTimeEntry, NetworkTimeEntry>(url, &time_entry.into())
+            .post::<NetworkTimeEntry, NetworkTimeEntry>(url, &body)
             .await?;
         return Ok(network_time_entry.id);
     }
 
     async fn update_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64> {
         let url = format!("{}/time_entries/{}", self.base_url, time_entry.id);
+        let id = time_entry.id;
+        let body: NetworkTimeEntry = time_entry.into();
+        if self.skip_request("PUT", &url, Some(&body)) {
+            return Ok(id);
+        }
         let network_time_entry = self
-            .put::<NetworkTimeEntry, NetworkTimeEntry>(url, &time_entry.into())
+            .put::<NetworkTimeEntry, NetworkTimeEntry>(url, &body)
             .await?;
         return Ok(network_time_entry.id);
     }
@@ -241,6 +519,9 @@ impl ApiClient for V9ApiClient {
             "{}/workspaces/{}/time_entries/{}",
             self.base_url, workspace_id, time_entry_id
         );
+        if self.skip_request::<()>("DELETE", &url, None) {
+            return Ok(());
+        }
         self.delete(url).await
     }
 
@@ -249,6 +530,7 @@ impl ApiClient for V9ApiClient {
         workspace_id: i64,
         name: String,
         color: String,
+        client_id: Option<i64>,
     ) -> ResultWithDefaultError<Project> {
         let url = format!("{}/workspaces/{}/projects", self.base_url, workspace_id);
         let body = NetworkCreateProject {
@@ -257,7 +539,18 @@ impl ApiClient for V9ApiClient {
             color,
             is_private: false,
             active: true,
+            client_id,
         };
+        if self.skip_request("POST", &url, Some(&body)) {
+            return Ok(Project {
+                id: 0,
+                name: body.name,
+                workspace_id,
+                color: body.color,
+                active: true,
+                ..Project::default()
+            });
+        }
         let network_project = self
             .post::<NetworkProject, NetworkCreateProject>(url, &body)
             .await?;
@@ -284,6 +577,9 @@ impl ApiClient for V9ApiClient {
             "{}/workspaces/{}/projects/{}",
             self.base_url, workspace_id, project_id
         );
+        if self.skip_request::<()>("DELETE", &url, None) {
+            return Ok(());
+        }
         self.delete(url).await
     }
 
@@ -298,6 +594,14 @@ impl ApiClient for V9ApiClient {
             self.base_url, workspace_id, project_id
         );
         let body = NetworkRenameProject { name: new_name };
+        if self.skip_request("PUT", &url, Some(&body)) {
+            return Ok(Project {
+                id: project_id,
+                name: body.name,
+                workspace_id,
+                ..Project::default()
+            });
+        }
         let network_project = self
             .put::<NetworkProject, NetworkRenameProject>(url, &body)
             .await?;
@@ -315,6 +619,95 @@ impl ApiClient for V9ApiClient {
         })
     }
 
+    async fn create_client(
+        &self,
+        workspace_id: i64,
+        name: String,
+    ) -> ResultWithDefaultError<crate::models::Client> {
+        let url = format!("{}/workspaces/{}/clients", self.base_url, workspace_id);
+        let body = NetworkCreateClient {
+            name,
+            wid: workspace_id,
+        };
+        if self.skip_request("POST", &url, Some(&body)) {
+            return Ok(crate::models::Client {
+                id: 0,
+                name: body.name,
+                workspace_id,
+            });
+        }
+        let network_client = self
+            .post::<NetworkClient, NetworkCreateClient>(url, &body)
+            .await?;
+        Ok(crate::models::Client {
+            id: network_client.id,
+            name: network_client.name,
+            workspace_id: network_client.wid,
+        })
+    }
+
+    async fn delete_client(&self, workspace_id: i64, client_id: i64) -> ResultWithDefaultError<()> {
+        let url = format!(
+            "{}/workspaces/{}/clients/{}",
+            self.base_url, workspace_id, client_id
+        );
+        if self.skip_request::<()>("DELETE", &url, None) {
+            return Ok(());
+        }
+        self.delete(url).await
+    }
+
+    async fn create_task(
+        &self,
+        workspace_id: i64,
+        project: Project,
+        name: String,
+    ) -> ResultWithDefaultError<Task> {
+        let url = format!(
+            "{}/workspaces/{}/projects/{}/tasks",
+            self.base_url, workspace_id, project.id
+        );
+        let body = NetworkCreateTask {
+            name,
+            workspace_id,
+            project_id: project.id,
+            active: true,
+        };
+        if self.skip_request("POST", &url, Some(&body)) {
+            return Ok(Task {
+                id: 0,
+                name: body.name,
+                workspace_id,
+                project,
+            });
+        }
+        let network_task = self
+            .post::<NetworkTask, NetworkCreateTask>(url, &body)
+            .await?;
+        Ok(Task {
+            id: network_task.id,
+            name: network_task.name,
+            workspace_id: network_task.workspace_id,
+            project,
+        })
+    }
+
+    async fn delete_task(
+        &self,
+        workspace_id: i64,
+        project_id: i64,
+        task_id: i64,
+    ) -> ResultWithDefaultError<()> {
+        let url = format!(
+            "{}/workspaces/{}/projects/{}/tasks/{}",
+            self.base_url, workspace_id, project_id, task_id
+        );
+        if self.skip_request::<()>("DELETE", &url, None) {
+            return Ok(());
+        }
+        self.delete(url).await
+    }
+
     async fn get_tags(&self, workspace_id: i64) -> ResultWithDefaultError<Vec<Tag>> {
         let network_tags = self.get_workspace_tags(workspace_id).await?;
         Ok(network_tags
@@ -330,6 +723,13 @@ impl ApiClient for V9ApiClient {
     async fn create_tag(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<Tag> {
         let url = format!("{}/workspaces/{}/tags", self.base_url, workspace_id);
         let body = NetworkCreateTag { name, workspace_id };
+        if self.skip_request("POST", &url, Some(&body)) {
+            return Ok(Tag {
+                id: 0,
+                name: body.name,
+                workspace_id,
+            });
+        }
         let network_tag = self
             .post::<NetworkTag, NetworkCreateTag>(url, &body)
             .await?;
@@ -345,6 +745,9 @@ impl ApiClient for V9ApiClient {
             "{}/workspaces/{}/tags/{}",
             self.base_url, workspace_id, tag_id
         );
+        if self.skip_request::<()>("DELETE", &url, None) {
+            return Ok(());
+        }
         self.delete(url).await
     }
 
@@ -362,6 +765,13 @@ impl ApiClient for V9ApiClient {
             name: new_name,
             workspace_id,
         };
+        if self.skip_request("PUT", &url, Some(&body)) {
+            return Ok(Tag {
+                id: tag_id,
+                name: body.name,
+                workspace_id,
+            });
+        }
         let network_tag = self.put::<NetworkTag, NetworkRenameTag>(url, &body).await?;
         Ok(Tag {
             id: network_tag.id,
@@ -375,87 +785,17 @@ impl ApiClient for V9ApiClient {
         since: Option<String>,
         until: Option<String>,
     ) -> ResultWithDefaultError<Vec<TimeEntry>> {
-        let (network_entries, network_projects, network_tasks, network_clients) = tokio::join!(
-            self.get_time_entries(since.as_deref(), until.as_deref()),
-            self.get_projects(),
-            self.get_tasks(),
-            self.get_clients(),
-        );
-
-        let clients: HashMap<i64, crate::models::Client> = network_clients
-            .unwrap_or_default()
-            .into_iter()
-            .map(|c| {
-                (
-                    c.id,
-                    crate::models::Client {
-                        id: c.id,
-                        name: c.name,
-                        workspace_id: c.wid,
-                    },
-                )
-            })
-            .collect();
-
-        let projects: HashMap<i64, Project> = network_projects
-            .unwrap_or_default()
-            .into_iter()
-            .map(|p| {
-                (
-                    p.id,
-                    Project {
-                        id: p.id,
-                        name: p.name.clone(),
-                        workspace_id: p.workspace_id,
-                        client: clients.get(&p.client_id.unwrap_or(-1)).cloned(),
-                        is_private: p.is_private,
-                        active: p.active,
-                        at: p.at,
-                        created_at: p.created_at,
-                        color: p.color,
-                        billable: p.billable,
-                    },
-                )
-            })
-            .collect();
-
-        let tasks: HashMap<i64, Task> = network_tasks
-            .unwrap_or_default()
-            .into_iter()
-            .filter_map(|t| {
-                projects.get(&t.project_id).map(|project| {
-                    (
-                        t.id,
-                        Task {
-                            id: t.id,
-                            name: t.name,
-                            project: project.clone(),
-                            workspace_id: t.workspace_id,
-                        },
-                    )
-                })
-            })
-            .collect();
-
-        let entries = network_entries
-            .unwrap_or_default()
-            .into_iter()
-            .map(|te| TimeEntry {
-                id: te.id,
-                description: te.description,
-                start: te.start,
-                stop: te.stop,
-                duration: te.duration,
-                billable: te.billable,
-                workspace_id: te.workspace_id,
-                tags: te.tags.unwrap_or_default(),
-                project: projects.get(&te.project_id.unwrap_or(-1)).cloned(),
-                task: tasks.get(&te.task_id.unwrap_or(-1)).cloned(),
-                ..Default::default()
-            })
-            .collect();
+        self.with_projects_and_tasks(self.get_time_entries(since.as_deref(), until.as_deref()))
+            .await
+    }
 
-        Ok(entries)
+    async fn get_time_entry(&self, id: i64) -> ResultWithDefaultError<TimeEntry> {
+        let url = format!("{}/me/time_entries/{}", self.base_url, id);
+        let request = async { self.get::<NetworkTimeEntry>(url).await.map(|te| vec![te]) };
+        self.with_projects_and_tasks(request)
+            .await?
+            .pop()
+            .ok_or_else(|| -> Box<dyn std::error::Error + Send> { Box::new(ApiError::NotFound) })
     }
 
     async fn get_entities(&self) -> ResultWithDefaultError<Entities> {
@@ -473,8 +813,7 @@ impl ApiClient for V9ApiClient {
             self.get_workspaces(),
         );
 
-        let clients: HashMap<i64, crate::models::Client> = network_clients
-            .unwrap_or_default()
+        let clients: HashMap<i64, crate::models::Client> = network_clients?
             .iter()
             .map(|c| {
                 (
@@ -488,8 +827,7 @@ impl ApiClient for V9ApiClient {
             })
             .collect();
 
-        let projects: HashMap<i64, Project> = network_projects
-            .unwrap_or_default()
+        let projects: HashMap<i64, Project> = network_projects?
             .iter()
             .map(|p| {
                 (
@@ -510,8 +848,7 @@ impl ApiClient for V9ApiClient {
             })
             .collect();
 
-        let tasks: HashMap<i64, Task> = network_tasks
-            .unwrap_or_default()
+        let tasks: HashMap<i64, Task> = network_tasks?
             .iter()
             .map(|t| {
                 (
@@ -526,8 +863,7 @@ impl ApiClient for V9ApiClient {
             })
             .collect();
 
-        let time_entries = network_time_entries
-            .unwrap_or_default()
+        let time_entries = network_time_entries?
             .iter()
             .map(|te| TimeEntry {
                 id: te.id,
@@ -544,8 +880,7 @@ impl ApiClient for V9ApiClient {
             })
             .collect();
 
-        let workspaces = network_workspaces
-            .unwrap_or_default()
+        let workspaces = network_workspaces?
             .iter()
             .map(|w| Workspace {
                 id: w.id,
@@ -564,3 +899,66 @@ impl ApiClient for V9ApiClient {
         })
     }
 }
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// Splits `[since, until)` into consecutive windows no longer than
+/// `TIME_ENTRIES_WINDOW_DAYS`.
+fn date_windows(since: NaiveDate, until: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut windows = Vec::new();
+    let mut window_start = since;
+    while window_start < until {
+        let window_end = std::cmp::min(
+            window_start + Duration::days(constants::TIME_ENTRIES_WINDOW_DAYS),
+            until,
+        );
+        windows.push((window_start, window_end));
+        window_start = window_end;
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    #[test]
+    fn retries_wait_as_asked_or_back_off() {
+        assert_eq!(retry_delay(Some("7"), 0).as_secs(), 7);
+        assert_eq!(retry_delay(None, 0).as_secs(), 1);
+        assert_eq!(
+            retry_delay(Some("Wed, 21 Oct 2026 07:28:00 GMT"), 2).as_secs(),
+            4
+        );
+    }
+
+    #[test]
+    fn a_short_range_is_a_single_window() {
+        let windows = date_windows(date("2024-01-01"), date("2024-01-15"));
+
+        assert_eq!(windows, vec![(date("2024-01-01"), date("2024-01-15"))]);
+    }
+
+    #[test]
+    fn a_long_range_is_split_into_contiguous_windows() {
+        let windows = date_windows(date("2024-01-01"), date("2024-04-01"));
+
+        assert_eq!(windows.first().unwrap().0, date("2024-01-01"));
+        assert_eq!(windows.last().unwrap().1, date("2024-04-01"));
+        assert!(windows.windows(2).all(|pair| pair[0].1 == pair[1].0));
+        assert!(windows
+            .iter()
+            .all(|(start, end)| (*end - *start).num_days() <= constants::TIME_ENTRIES_WINDOW_DAYS));
+    }
+
+    #[test]
+    fn an_empty_range_has_no_windows() {
+        assert!(date_windows(date("2024-01-02"), date("2024-01-01")).is_empty());
+    }
+}
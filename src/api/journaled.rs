@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::journal::{Journal, Operation};
+use crate::models::{
+    Client, Entities, Project, ResultWithDefaultError, Tag, Task, TimeEntry, User,
+};
+
+use super::client::ApiClient;
+
+/// Entities returned by earlier lookups, used as the "before" state of the
+/// changes that follow. Commands always look an entity up before changing it,
+/// so this saves fetching it again.
+#[derive(Default)]
+struct Seen {
+    time_entries: HashMap<i64, TimeEntry>,
+    projects: HashMap<i64, Project>,
+    tags: HashMap<i64, Tag>,
+}
+
+/// Records every create, update and delete of time entries, projects and tags,
+/// and the clients and tasks created, made through the wrapped client in the
+/// operation journal, so that
+/// `toggl undo` can revert them. Without a journal it only forwards calls.
+pub struct JournaledApiClient<C: ApiClient> {
+    inner: C,
+    journal: Option<Journal>,
+    seen: Mutex<Seen>,
+}
+
+impl<C: ApiClient> JournaledApiClient<C> {
+    pub fn new(inner: C, journal: Option<Journal>) -> JournaledApiClient<C> {
+        JournaledApiClient {
+            inner,
+            journal,
+            seen: Mutex::new(Seen::default()),
+        }
+    }
+
+    fn record(&self, operation: Operation) {
+        if let Some(journal) = &self.journal {
+            // The change itself went through, so a journal that can't be
+            // written only costs the ability to undo it.
+            if let Err(error) = journal.record(operation) {
+                eprint!("{error}");
+            }
+        }
+    }
+
+    fn remember_time_entries<'a>(&self, time_entries: impl Iterator<Item = &'a TimeEntry>) {
+        let mut seen = self.seen.lock().unwrap();
+        seen.time_entries
+            .extend(time_entries.map(|te| (te.id, te.clone())));
+    }
+
+    fn remember_tags<'a>(&self, tags: impl Iterator<Item = &'a Tag>) {
+        let mut seen = self.seen.lock().unwrap();
+        seen.tags.extend(tags.map(|t| (t.id, t.clone())));
+    }
+}
+
+impl<C: ApiClient + Send + Sync> JournaledApiClient<C> {
+    async fn seen_time_entry(&self, id: i64) -> Option<TimeEntry> {
+        self.journal.as_ref()?;
+        if !self.seen.lock().unwrap().time_entries.contains_key(&id) {
+            let _ = self.get_entities().await;
+        }
+        self.seen.lock().unwrap().time_entries.get(&id).cloned()
+    }
+
+    async fn seen_project(&self, id: i64) -> Option<Project> {
+        self.journal.as_ref()?;
+        if !self.seen.lock().unwrap().projects.contains_key(&id) {
+            let _ = self.get_entities().await;
+        }
+        self.seen.lock().unwrap().projects.get(&id).cloned()
+    }
+
+    async fn seen_tag(&self, workspace_id: i64, id: i64) -> Option<Tag> {
+        self.journal.as_ref()?;
+        if !self.seen.lock().unwrap().tags.contains_key(&id) {
+            let _ = self.get_tags(workspace_id).await;
+        }
+        self.seen.lock().unwrap().tags.get(&id).cloned()
+    }
+}
+
+#[async_trait]
+impl<C: ApiClient + Send + Sync> ApiClient for JournaledApiClient<C> {
+    async fn get_user(&self) -> ResultWithDefaultError<User> {
+        self.inner.get_user().await
+    }
+
+    async fn get_entities(&self) -> ResultWithDefaultError<Entities> {
+        let entities = self.inner.get_entities().await?;
+        self.remember_time_entries(entities.time_entries.iter());
+        self.remember_tags(entities.tags.iter());
+        self.seen.lock().unwrap().projects.extend(
+            entities
+                .projects
+                .iter()
+                .map(|(id, project)| (*id, project.clone())),
+        );
+        Ok(entities)
+    }
+
+    async fn create_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64> {
+        let id = self.inner.create_time_entry(time_entry.clone()).await?;
+        let after = TimeEntry { id, ..time_entry };
+        self.remember_time_entries(std::iter::once(&after));
+        self.record(Operation::CreateTimeEntry { after });
+        Ok(id)
+    }
+
+    async fn update_time_entry(&self, time_entry: TimeEntry) -> ResultWithDefaultError<i64> {
+        let before = self.seen_time_entry(time_entry.id).await;
+        let id = self.inner.update_time_entry(time_entry.clone()).await?;
+        self.remember_time_entries(std::iter::once(&time_entry));
+        self.record(Operation::UpdateTimeEntry {
+            before,
+            after: time_entry,
+        });
+        Ok(id)
+    }
+
+    async fn get_time_entries_filtered(
+        &self,
+        since: Option<String>,
+        until: Option<String>,
+    ) -> ResultWithDefaultError<Vec<TimeEntry>> {
+        let time_entries = self.inner.get_time_entries_filtered(since, until).await?;
+        self.remember_time_entries(time_entries.iter());
+        Ok(time_entries)
+    }
+
+    async fn get_time_entry(&self, id: i64) -> ResultWithDefaultError<TimeEntry> {
+        let time_entry = self.inner.get_time_entry(id).await?;
+        self.remember_time_entries(std::iter::once(&time_entry));
+        Ok(time_entry)
+    }
+
+    async fn delete_time_entry(
+        &self,
+        workspace_id: i64,
+        time_entry_id: i64,
+    ) -> ResultWithDefaultError<()> {
+        let before = self.seen_time_entry(time_entry_id).await;
+        self.inner
+            .delete_time_entry(workspace_id, time_entry_id)
+            .await?;
+        self.record(Operation::DeleteTimeEntry {
+            workspace_id,
+            id: time_entry_id,
+            before,
+        });
+        Ok(())
+    }
+
+    async fn create_project(
+        &self,
+        workspace_id: i64,
+        name: String,
+        color: String,
+        client_id: Option<i64>,
+    ) -> ResultWithDefaultError<Project> {
+        let project = self
+            .inner
+            .create_project(workspace_id, name, color, client_id)
+            .await?;
+        self.record(Operation::CreateProject {
+            after: project.clone(),
+        });
+        Ok(project)
+    }
+
+    async fn delete_project(
+        &self,
+        workspace_id: i64,
+        project_id: i64,
+    ) -> ResultWithDefaultError<()> {
+        let before = self.seen_project(project_id).await;
+        self.inner.delete_project(workspace_id, project_id).await?;
+        self.record(Operation::DeleteProject {
+            workspace_id,
+            id: project_id,
+            before,
+        });
+        Ok(())
+    }
+
+    async fn rename_project(
+        &self,
+        workspace_id: i64,
+        project_id: i64,
+        new_name: String,
+    ) -> ResultWithDefaultError<Project> {
+        let before = self.seen_project(project_id).await;
+        let project = self
+            .inner
+            .rename_project(workspace_id, project_id, new_name)
+            .await?;
+        self.record(Operation::RenameProject {
+            before,
+            after: project.clone(),
+        });
+        Ok(project)
+    }
+
+    async fn create_client(
+        &self,
+        workspace_id: i64,
+        name: String,
+    ) -> ResultWithDefaultError<Client> {
+        let client = self.inner.create_client(workspace_id, name).await?;
+        self.record(Operation::CreateClient {
+            after: client.clone(),
+        });
+        Ok(client)
+    }
+
+    async fn delete_client(&self, workspace_id: i64, client_id: i64) -> ResultWithDefaultError<()> {
+        self.inner.delete_client(workspace_id, client_id).await
+    }
+
+    async fn create_task(
+        &self,
+        workspace_id: i64,
+        project: Project,
+        name: String,
+    ) -> ResultWithDefaultError<Task> {
+        let task = self.inner.create_task(workspace_id, project, name).await?;
+        self.record(Operation::CreateTask {
+            after: task.clone(),
+        });
+        Ok(task)
+    }
+
+    async fn delete_task(
+        &self,
+        workspace_id: i64,
+        project_id: i64,
+        task_id: i64,
+    ) -> ResultWithDefaultError<()> {
+        self.inner
+            .delete_task(workspace_id, project_id, task_id)
+            .await
+    }
+
+    async fn get_tags(&self, workspace_id: i64) -> ResultWithDefaultError<Vec<Tag>> {
+        let tags = self.inner.get_tags(workspace_id).await?;
+        self.remember_tags(tags.iter());
+        Ok(tags)
+    }
+
+    async fn create_tag(&self, workspace_id: i64, name: String) -> ResultWithDefaultError<Tag> {
+        let tag = self.inner.create_tag(workspace_id, name).await?;
+        self.record(Operation::CreateTag { after: tag.clone() });
+        Ok(tag)
+    }
+
+    async fn delete_tag(&self, workspace_id: i64, tag_id: i64) -> ResultWithDefaultError<()> {
+        let before = self.seen_tag(workspace_id, tag_id).await;
+        self.inner.delete_tag(workspace_id, tag_id).await?;
+        self.record(Operation::DeleteTag {
+            workspace_id,
+            id: tag_id,
+            before,
+        });
+        Ok(())
+    }
+
+    async fn rename_tag(
+        &self,
+        workspace_id: i64,
+        tag_id: i64,
+        new_name: String,
+    ) -> ResultWithDefaultError<Tag> {
+        let before = self.seen_tag(workspace_id, tag_id).await;
+        let tag = self
+            .inner
+            .rename_tag(workspace_id, tag_id, new_name)
+            .await?;
+        self.record(Operation::RenameTag {
+            before,
+            after: tag.clone(),
+        });
+        Ok(tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+
+    #[tokio::test]
+    async fn a_deleted_time_entry_is_journaled_with_its_fields() {
+        let directory = tempfile::tempdir().unwrap();
+        let journal_path = directory.path().join("default.jsonl");
+        let time_entry = TimeEntry {
+            id: 7,
+            description: "Standup".to_string(),
+            ..TimeEntry::default()
+        };
+        let mut inner = MockApiClient::new();
+        let entities = Entities {
+            time_entries: vec![time_entry.clone()],
+            ..Entities::default()
+        };
+        inner
+            .expect_get_entities()
+            .returning(move || Ok(entities.clone()));
+        inner.expect_delete_time_entry().returning(|_, _| Ok(()));
+        let api_client =
+            JournaledApiClient::new(inner, Some(Journal::new(journal_path.clone(), 30)));
+
+        api_client.get_entities().await.unwrap();
+        api_client.delete_time_entry(1, 7).await.unwrap();
+
+        let entries = Journal::new(journal_path, 30).read().unwrap();
+        match &entries[0].operation {
+            Operation::DeleteTimeEntry {
+                id: 7,
+                before: Some(before),
+                ..
+            } => assert_eq!(before.description, "Standup"),
+            operation => panic!("unexpected operation {operation:?}"),
+        }
+    }
+}
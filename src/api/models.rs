@@ -69,6 +69,22 @@ pub struct NetworkCreateProject {
     pub color: String,
     pub is_private: bool,
     pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NetworkCreateClient {
+    pub name: String,
+    pub wid: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NetworkCreateTask {
+    pub name: String,
+    pub workspace_id: i64,
+    pub project_id: i64,
+    pub active: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
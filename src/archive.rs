@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ArchiveError;
+use crate::models::{Client, Project, ResultWithDefaultError, Tag, Task, TimeEntry, Workspace};
+
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// A full copy of the account data, written by `toggl backup` and read back by
+/// `toggl restore`. The `version` field is bumped whenever the layout changes
+/// in a way older versions of the CLI can't read.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Archive {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub workspaces: Vec<Workspace>,
+    pub clients: Vec<Client>,
+    pub projects: Vec<Project>,
+    pub tasks: Vec<Task>,
+    pub tags: Vec<Tag>,
+    pub time_entries: Vec<TimeEntry>,
+}
+
+impl Archive {
+    pub fn read(path: &Path) -> ResultWithDefaultError<Archive> {
+        let contents = fs::read_to_string(path).map_err(|e| read_error(path, e))?;
+        let archive: Archive = serde_json::from_str(&contents).map_err(|e| read_error(path, e))?;
+        if archive.version > ARCHIVE_VERSION {
+            return Err(Box::new(ArchiveError::UnsupportedVersion(archive.version)));
+        }
+        Ok(archive)
+    }
+
+    pub fn write(&self, path: &Path) -> ResultWithDefaultError<()> {
+        let contents =
+            serde_json::to_string_pretty(self).expect("failed to serialize archive to JSON");
+        fs::write(path, contents).map_err(|e| write_error(path, e))
+    }
+}
+
+/// Maps the ids stored in an archive to the ids of the entities recreated from
+/// it. It is saved next to the archive after every step so an interrupted
+/// restore can pick up where it left off.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RestoreProgress {
+    pub workspace_id: i64,
+    pub clients: HashMap<i64, i64>,
+    pub projects: HashMap<i64, i64>,
+    pub tasks: HashMap<i64, i64>,
+    pub tags: HashMap<i64, i64>,
+    pub time_entries: HashMap<i64, i64>,
+}
+
+impl RestoreProgress {
+    pub fn path_for(archive_path: &Path) -> PathBuf {
+        let mut file_name = archive_path.as_os_str().to_owned();
+        file_name.push(".progress");
+        PathBuf::from(file_name)
+    }
+
+    pub fn load(path: &Path) -> Option<RestoreProgress> {
+        let contents = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> ResultWithDefaultError<()> {
+        let contents = serde_json::to_string(self).expect("failed to serialize restore progress");
+        fs::write(path, contents).map_err(|e| write_error(path, e))
+    }
+}
+
+fn read_error(path: &Path, error: impl std::fmt::Display) -> Box<dyn std::error::Error + Send> {
+    Box::new(ArchiveError::Read(path.to_path_buf(), error.to_string()))
+}
+
+fn write_error(path: &Path, error: impl std::fmt::Display) -> Box<dyn std::error::Error + Send> {
+    Box::new(ArchiveError::Write(path.to_path_buf(), error.to_string()))
+}
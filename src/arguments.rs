@@ -11,11 +11,68 @@ pub struct CommandLineArguments {
     #[structopt(short = "C", help = "Change directory before running the command")]
     pub directory: Option<PathBuf>,
 
-    #[structopt(long, help = "Use custom proxy")]
+    #[structopt(long, env = "TOGGL_PROXY", help = "Use custom proxy")]
     pub proxy: Option<String>,
 
     #[structopt(long, help = "Use fzf instead of the default picker")]
     pub fzf: bool,
+
+    #[structopt(
+        long,
+        env = "TOGGL_PICKER",
+        help = "Picker to use: skim, fzf, menu, a command named in the profile's picker_commands, or a dmenu-style command line such as \"rofi -dmenu -i\""
+    )]
+    pub picker: Option<String>,
+
+    #[structopt(
+        short,
+        long,
+        global = true,
+        help = "Print the resulting entities as JSON instead of text"
+    )]
+    pub json: bool,
+
+    #[structopt(
+        long,
+        global = true,
+        env = "TOGGL_ERROR_FORMAT",
+        default_value = "human",
+        possible_values = &["human", "json"],
+        help = "Format of error messages written to stderr"
+    )]
+    pub error_format: String,
+
+    #[structopt(
+        short,
+        long,
+        global = true,
+        help = "Answer yes to confirmation prompts, which are refused when stdin is not a terminal"
+    )]
+    pub yes: bool,
+
+    #[structopt(
+        long,
+        global = true,
+        help = "Run all lookups but print the requests that would change something instead of sending them"
+    )]
+    pub dry_run: bool,
+
+    #[structopt(
+        short,
+        long,
+        global = true,
+        env = "TOGGL_WORKSPACE",
+        help = "Name or id of the workspace to use instead of your default workspace"
+    )]
+    pub workspace: Option<String>,
+
+    #[structopt(
+        long,
+        global = true,
+        env = "TOGGL_PROFILE",
+        help = "Name of the profile (account and preferences) to use"
+    )]
+    pub profile: Option<String>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -25,8 +82,6 @@ pub enum Command {
     List {
         #[structopt(short, long)]
         number: Option<usize>,
-        #[structopt(short, long, help = "Output in JSON format")]
-        json: bool,
         #[structopt(
             long,
             help = "Filter entries starting on or after this date (YYYY-MM-DD)"
@@ -37,6 +92,11 @@ pub enum Command {
             help = "Filter entries starting on or before this date (YYYY-MM-DD)"
         )]
         until: Option<String>,
+        #[structopt(
+            long,
+            help = "List from all your workspaces, labelling each row with its workspace"
+        )]
+        all_workspaces: bool,
         #[structopt(subcommand)]
         entity: Option<Entity>,
     },
@@ -48,6 +108,8 @@ pub enum Command {
     Auth {
         #[structopt(help = "API token (omit to be prompted)")]
         api_token: Option<String>,
+        #[structopt(subcommand)]
+        cmd: Option<AuthSubCommand>,
     },
     #[structopt(about = "Clear stored credentials")]
     Logout,
@@ -62,7 +124,7 @@ pub enum Command {
         #[structopt(
             short,
             long,
-            help = "Exact name of the project you want the time entry to be associated with"
+            help = "Project for the time entry, matched ignoring case and fuzzily; several or loose matches open the picker"
         )]
         project: Option<String>,
         #[structopt(
@@ -73,15 +135,59 @@ pub enum Command {
         tags: Option<Vec<String>>,
         #[structopt(
             long,
-            help = "Exact name of the task you want the time entry to be associated with"
+            help = "Task for the time entry, matched like --project within the chosen project"
         )]
         task: Option<String>,
         #[structopt(short, long)]
         billable: bool,
+        #[structopt(long, help = "Create the project if nothing matches --project")]
+        create_project: bool,
+        #[structopt(
+            long,
+            help = "Create the task in the project if nothing matches --task"
+        )]
+        create_task: bool,
+        #[structopt(long, help = "Create tags that don't exist yet without asking")]
+        create_tags: bool,
     },
+    #[structopt(
+        about = "Start a copy of a past time entry, the most recent stopped one unless an id, --match or --interactive is given"
+    )]
     Continue {
-        #[structopt(short, long)]
+        #[structopt(short, long, conflicts_with_all = &["id", "match"])]
         interactive: bool,
+        #[structopt(help = "ID of the time entry to continue")]
+        id: Option<i64>,
+        #[structopt(
+            name = "match",
+            short = "m",
+            long = "match",
+            conflicts_with = "id",
+            help = "Continue the newest entry whose description contains this text (case-insensitive)"
+        )]
+        search: Option<String>,
+        #[structopt(short, long, help = "Description for the new entry")]
+        description: Option<String>,
+        #[structopt(
+            short,
+            long,
+            help = "Project name for the new entry (use empty string \"\" to remove project)"
+        )]
+        project: Option<String>,
+        #[structopt(
+            short,
+            long,
+            help = "Space-separated list of tags for the new entry (use empty string \"\" to clear tags)"
+        )]
+        tags: Option<Vec<String>>,
+        #[structopt(short, long, help = "Make the new entry billable")]
+        billable: bool,
+        #[structopt(
+            long,
+            conflicts_with = "billable",
+            help = "Make the new entry non-billable"
+        )]
+        no_billable: bool,
     },
     #[structopt(about = "Create a new project in your workspace")]
     CreateProject {
@@ -124,12 +230,21 @@ pub enum Command {
         #[structopt(help = "New name for the tag")]
         new_name: String,
     },
-    #[structopt(about = "Edit a time entry, opens it in $EDITOR when no edit flags are given")]
+    #[structopt(
+        about = "Edit a time entry, opens it in $EDITOR when no edit flags are given. With --since/--until all matching entries are edited in one buffer"
+    )]
     Edit {
         #[structopt(
             help = "ID of the time entry to edit (omit to edit the currently running entry)"
         )]
         id: Option<i64>,
+        #[structopt(
+            short,
+            long,
+            conflicts_with = "id",
+            help = "Pick the time entry to edit from a list"
+        )]
+        interactive: bool,
         #[structopt(short, long, help = "New description")]
         description: Option<String>,
         #[structopt(
@@ -144,6 +259,12 @@ pub enum Command {
             help = "New space-separated list of tags (use empty string \"\" to clear tags)"
         )]
         tags: Option<Vec<String>>,
+        #[structopt(
+            long,
+            conflicts_with = "tags",
+            help = "Choose the tags from a list, starting from the entry's current tags"
+        )]
+        pick_tags: bool,
         #[structopt(
             long,
             help = "New start time as an RFC3339 timestamp, e.g. 2026-05-11T07:39:45Z"
@@ -154,12 +275,62 @@ pub enum Command {
             help = "New stop time as an RFC3339 timestamp (use empty string \"\" to mark the entry running again)"
         )]
         stop_time: Option<String>,
+        #[structopt(
+            long,
+            conflicts_with_all = &["id", "interactive", "description", "project", "tags", "pick-tags", "start-time", "stop-time"],
+            help = "Edit all entries starting on or after this date (YYYY-MM-DD) in one buffer"
+        )]
+        since: Option<String>,
+        #[structopt(
+            long,
+            conflicts_with_all = &["id", "interactive", "description", "project", "tags", "pick-tags", "start-time", "stop-time"],
+            help = "Edit all entries starting on or before this date (YYYY-MM-DD) in one buffer"
+        )]
+        until: Option<String>,
     },
-    #[structopt(about = "Delete a time entry by ID")]
+    #[structopt(about = "Delete a time entry by ID, or pick the entries to delete from a list")]
     Delete {
-        #[structopt(help = "ID of the time entry to delete")]
-        id: i64,
+        #[structopt(help = "ID of the time entry to delete (omit to pick entries from a list)")]
+        id: Option<i64>,
+        #[structopt(
+            short,
+            long,
+            conflicts_with = "id",
+            help = "Pick one or more time entries to delete from a list"
+        )]
+        interactive: bool,
+    },
+    #[structopt(
+        about = "Write all workspaces, clients, projects, tasks, tags and time entries to a JSON archive"
+    )]
+    Backup {
+        #[structopt(help = "Path of the archive to write")]
+        file: PathBuf,
+        #[structopt(
+            long,
+            help = "Back up time entries starting on or after this date (YYYY-MM-DD), defaults to the account creation date"
+        )]
+        since: Option<String>,
+    },
+    #[structopt(
+        about = "Recreate the contents of a backup archive in a workspace, pick it with --workspace. With --dry-run it prints what would be created"
+    )]
+    Restore {
+        #[structopt(help = "Path of the archive to restore")]
+        file: PathBuf,
+    },
+    #[structopt(about = "Revert the last operations recorded in the journal")]
+    Undo {
+        #[structopt(default_value = "1", help = "Number of operations to revert")]
+        count: usize,
     },
+    #[structopt(about = "Show the operations recorded in the journal, newest first")]
+    History {
+        #[structopt(short, long, help = "Number of operations to show")]
+        number: Option<usize>,
+    },
+    #[structopt(about = "Pick a recent time entry, project or tag and an action to perform on it")]
+    Browse,
     #[structopt(about = "Manage auto-tracking configuration")]
     Config {
         #[structopt(
@@ -181,18 +352,14 @@ pub enum Command {
 }
 #[derive(Debug, StructOpt)]
 pub enum Entity {
-    Project {
-        #[structopt(short, long, help = "Output in JSON format")]
-        json: bool,
-    },
-    TimeEntry {
-        #[structopt(short, long, help = "Output in JSON format")]
-        json: bool,
-    },
-    Tag {
-        #[structopt(short, long, help = "Output in JSON format")]
-        json: bool,
-    },
+    Project,
+    TimeEntry,
+    Tag,
+}
+#[derive(Debug, StructOpt)]
+pub enum AuthSubCommand {
+    #[structopt(about = "Show the authenticated account, credential source and workspaces")]
+    Status,
 }
 #[derive(Debug, StructOpt)]
 pub enum ConfigSubCommand {
@@ -200,4 +367,16 @@ pub enum ConfigSubCommand {
     Init,
     #[structopt(about = "Report matching configuration block for current directory.")]
     Active,
+    #[structopt(about = "Show the settings of the active profile.")]
+    Profile {
+        #[structopt(short, long, help = "Edit the profiles file in $EDITOR")]
+        edit: bool,
+    },
+    #[structopt(about = "Show, edit or validate the global configuration.")]
+    Global {
+        #[structopt(short, long, help = "Edit the global configuration file in $EDITOR")]
+        edit: bool,
+        #[structopt(long, help = "Check the global configuration file for errors")]
+        validate: bool,
+    },
 }
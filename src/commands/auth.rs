@@ -1,14 +1,17 @@
 use crate::api::client::ApiClient;
 use crate::credentials;
+use crate::error::{ApiError, StorageError};
 use crate::models;
 use colored::Colorize;
-use credentials::CredentialsStorage;
-use models::ResultWithDefaultError;
+use credentials::{Credentials, CredentialsStorage};
+use models::{ResultWithDefaultError, Workspace};
+use serde::Serialize;
 use std::io::Write;
 
 pub struct AuthenticationCommand;
 
 const AUTH_SUCCEEDED_MESSAGE: &str = "Successfully authenticated for user with email:";
+const CREDENTIALS_SAVED_MESSAGE: &str = "Credentials saved to:";
 
 impl AuthenticationCommand {
     pub async fn execute<W: Write>(
@@ -25,6 +28,145 @@ impl AuthenticationCommand {
             user.email.green().bold(),
         )
         .expect("failed to write to stdout");
+        writeln!(
+            writer,
+            "{} {}",
+            CREDENTIALS_SAVED_MESSAGE,
+            credentials_storage.describe()
+        )
+        .expect("failed to write to stdout");
+
+        Ok(())
+    }
+}
+
+pub struct AuthenticationStatusCommand;
+
+/// Report printed by `toggl auth status`. The account fields are only present
+/// when a token is stored and still accepted by the API.
+#[derive(Serialize, Debug)]
+struct AuthenticationStatus {
+    source: String,
+    stored: bool,
+    valid: bool,
+    email: Option<String>,
+    fullname: Option<String>,
+    timezone: Option<String>,
+    default_workspace: Option<Workspace>,
+    workspaces: Vec<Workspace>,
+}
+
+impl AuthenticationStatusCommand {
+    /// Reads the stored token and checks it with a client made by
+    /// `create_client`. A missing token is reported rather than an error.
+    pub async fn execute<W: Write, A: ApiClient>(
+        mut writer: W,
+        credentials_storage: Box<dyn CredentialsStorage>,
+        create_client: impl FnOnce(Credentials) -> ResultWithDefaultError<A>,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
+        let mut status = AuthenticationStatus {
+            source: credentials_storage.describe(),
+            stored: false,
+            valid: false,
+            email: None,
+            fullname: None,
+            timezone: None,
+            default_workspace: None,
+            workspaces: Vec::new(),
+        };
+
+        let api_client = match credentials_storage.read() {
+            Err(error)
+                if matches!(
+                    error.downcast_ref::<StorageError>(),
+                    Some(StorageError::Read)
+                ) =>
+            {
+                None
+            }
+            Err(error) => return Err(error),
+            Ok(credentials) => Some(create_client(credentials)?),
+        };
+        status.stored = api_client.is_some();
+
+        match api_client {
+            None => {}
+            Some(api_client) => match api_client.get_user().await {
+                Err(error)
+                    if matches!(
+                        error.downcast_ref::<ApiError>(),
+                        Some(ApiError::Unauthorized)
+                    ) => {}
+                Err(error) => return Err(error),
+                Ok(user) => {
+                    let entities = api_client.get_entities().await?;
+                    status.valid = true;
+                    status.default_workspace = entities
+                        .workspaces
+                        .iter()
+                        .find(|w| w.id == user.default_workspace_id)
+                        .cloned();
+                    status.workspaces = entities.workspaces;
+                    status.email = Some(user.email);
+                    status.fullname = user.fullname;
+                    status.timezone = Some(user.timezone);
+                }
+            },
+        }
+
+        if json {
+            let json_string =
+                serde_json::to_string_pretty(&status).expect("failed to serialize to JSON");
+            writeln!(writer, "{json_string}").expect("failed to write to stdout");
+            return Ok(());
+        }
+
+        let field = |name: &str, value: &str| format!("{}: {}", name.green(), value);
+        let mut lines = vec![
+            field("Credentials", &status.source),
+            field(
+                "Token",
+                &if status.valid {
+                    "valid".green().to_string()
+                } else if status.stored {
+                    "invalid, run toggl auth to replace it".red().to_string()
+                } else {
+                    "none, run toggl auth to set one".red().to_string()
+                },
+            ),
+        ];
+        if status.valid {
+            lines.push(field("Email", status.email.as_deref().unwrap_or_default()));
+            lines.push(field(
+                "Name",
+                status.fullname.as_deref().unwrap_or_default(),
+            ));
+            lines.push(field(
+                "Timezone",
+                status.timezone.as_deref().unwrap_or_default(),
+            ));
+            if let Some(workspace) = &status.default_workspace {
+                lines.push(field(
+                    "Default workspace",
+                    &format!("{} ({})", workspace.name, workspace.id),
+                ));
+            }
+            lines.push(format!("{}:", "Workspaces".green()));
+            for workspace in status.workspaces.iter() {
+                lines.push(format!(
+                    "  {} ({}){}",
+                    workspace.name,
+                    workspace.id,
+                    if workspace.admin {
+                        " admin".yellow().to_string()
+                    } else {
+                        String::new()
+                    }
+                ));
+            }
+        }
+        writeln!(writer, "{}", lines.join("\n")).expect("failed to write to stdout");
 
         Ok(())
     }
@@ -36,8 +178,8 @@ mod tests {
     use crate::api::client::MockApiClient;
     use crate::error;
     use credentials::MockCredentialsStorage;
-    use error::{ApiError, StorageError};
-    use models::User;
+    use error::StorageError;
+    use models::{Entities, User};
     use tokio_test::assert_err;
     use tokio_test::assert_ok;
 
@@ -53,6 +195,7 @@ mod tests {
             fullname: Some(MOCK_USER_NAME.to_string()),
             timezone: "UTC".to_string(),
             default_workspace_id: 1,
+            created_at: None,
         };
 
         api_client
@@ -75,6 +218,9 @@ mod tests {
         let mut credentials_storage = MockCredentialsStorage::new();
         credentials_storage.expect_persist().returning(|_| Ok(()));
         credentials_storage
+            .expect_describe()
+            .returning(|| "keyring".to_string());
+        credentials_storage
     }
 
     fn create_failing_credentials_storage() -> MockCredentialsStorage {
@@ -85,6 +231,19 @@ mod tests {
         credentials_storage
     }
 
+    fn create_describing_credentials_storage() -> MockCredentialsStorage {
+        let mut credentials_storage = MockCredentialsStorage::new();
+        credentials_storage
+            .expect_describe()
+            .returning(|| "keyring".to_string());
+        credentials_storage.expect_read().returning(|| {
+            Ok(Credentials {
+                api_token: MOCK_API_TOKEN.to_string(),
+            })
+        });
+        credentials_storage
+    }
+
     #[tokio::test]
     async fn a_valid_api_call_and_a_working_storage_returns_an_ok_result() {
         // Arrange
@@ -112,9 +271,10 @@ mod tests {
 
         // Assert
         let expected_output = format!(
-            "{} {}\n",
+            "{} {}\n{} keyring\n",
             AUTH_SUCCEEDED_MESSAGE.green(),
-            MOCK_EMAIL.green().bold()
+            MOCK_EMAIL.green().bold(),
+            CREDENTIALS_SAVED_MESSAGE
         );
         let actual_output = String::from_utf8(output)
             .unwrap_or_else(|_| panic!("empty output when {expected_output} was expected"));
@@ -151,4 +311,94 @@ mod tests {
         // Assert
         assert_err!(result);
     }
+
+    #[tokio::test]
+    async fn status_reports_the_account_and_its_workspaces_as_json() {
+        // Arrange
+        let mut output = Vec::new();
+        let mut api_client = create_working_api_client();
+        api_client.expect_get_entities().returning(|| {
+            Ok(Entities {
+                workspaces: vec![Workspace {
+                    id: 1,
+                    name: "Acme".to_string(),
+                    admin: true,
+                }],
+                ..Default::default()
+            })
+        });
+        let credentials_storage = Box::new(create_describing_credentials_storage());
+
+        // Act
+        let result = AuthenticationStatusCommand::execute(
+            &mut output,
+            credentials_storage,
+            |_| Ok(api_client),
+            true,
+        )
+        .await;
+
+        // Assert
+        assert_ok!(result);
+        let status: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(status["source"], "keyring");
+        assert_eq!(status["valid"], true);
+        assert_eq!(status["email"], MOCK_EMAIL);
+        assert_eq!(status["default_workspace"]["name"], "Acme");
+        assert_eq!(status["workspaces"][0]["admin"], true);
+    }
+
+    #[tokio::test]
+    async fn status_reports_a_rejected_token_as_invalid() {
+        // Arrange
+        let mut output = Vec::new();
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_user()
+            .returning(|| Err(Box::new(ApiError::Unauthorized)));
+        let credentials_storage = Box::new(create_describing_credentials_storage());
+
+        // Act
+        let result = AuthenticationStatusCommand::execute(
+            &mut output,
+            credentials_storage,
+            |_| Ok(api_client),
+            true,
+        )
+        .await;
+
+        // Assert
+        assert_ok!(result);
+        let status: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(status["valid"], false);
+        assert!(status["email"].is_null());
+    }
+
+    #[tokio::test]
+    async fn status_reports_a_missing_token_instead_of_failing() {
+        // Arrange
+        let mut output = Vec::new();
+        let mut credentials_storage = MockCredentialsStorage::new();
+        credentials_storage
+            .expect_describe()
+            .returning(|| "keyring".to_string());
+        credentials_storage
+            .expect_read()
+            .returning(|| Err(Box::new(StorageError::Read)));
+
+        // Act
+        let result = AuthenticationStatusCommand::execute(
+            &mut output,
+            Box::new(credentials_storage),
+            |_| Ok(MockApiClient::new()),
+            true,
+        )
+        .await;
+
+        // Assert
+        assert_ok!(result);
+        let status: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(status["stored"], false);
+        assert_eq!(status["valid"], false);
+    }
 }
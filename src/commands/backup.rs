@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+
+use crate::api::client::ApiClient;
+use crate::archive::{Archive, ARCHIVE_VERSION};
+use crate::constants;
+use crate::models::ResultWithDefaultError;
+use crate::output::print_json;
+use crate::utilities;
+use chrono::{Duration, Utc};
+use colored::Colorize;
+
+pub struct BackupCommand;
+
+impl BackupCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        path: PathBuf,
+        since: Option<String>,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
+        utilities::check_date_argument("--since", since.as_deref())?;
+        let user = api_client.get_user().await?;
+        let entities = api_client.get_entities().await?;
+
+        let mut tags = Vec::new();
+        for workspace in entities.workspaces.iter() {
+            tags.extend(api_client.get_tags(workspace.id).await?);
+        }
+
+        let since = match since {
+            Some(since) => since,
+            None => user
+                .created_at
+                .map(|created_at| created_at.date_naive().to_string())
+                .unwrap_or_else(|| constants::BACKUP_EARLIEST_DATE.to_string()),
+        };
+        let until = (Utc::now().date_naive() + Duration::days(1)).to_string();
+        let mut time_entries = api_client
+            .get_time_entries_filtered(Some(since), Some(until))
+            .await?;
+        time_entries.sort_by_key(|te| te.start);
+
+        let archive = Archive {
+            version: ARCHIVE_VERSION,
+            created_at: Utc::now(),
+            workspaces: entities.workspaces,
+            clients: entities.clients.into_values().collect(),
+            projects: entities.projects.into_values().collect(),
+            tasks: entities.tasks.into_values().collect(),
+            tags,
+            time_entries,
+        };
+        archive.write(&path)?;
+
+        if json {
+            print_json(&serde_json::json!({
+                "path": path,
+                "workspaces": archive.workspaces.len(),
+                "clients": archive.clients.len(),
+                "projects": archive.projects.len(),
+                "tasks": archive.tasks.len(),
+                "tags": archive.tags.len(),
+                "time_entries": archive.time_entries.len(),
+            }));
+            return Ok(());
+        }
+        println!(
+            "{} {}\n{} workspaces, {} clients, {} projects, {} tasks, {} tags, {} time entries",
+            "Backup written to".green(),
+            path.display(),
+            archive.workspaces.len(),
+            archive.clients.len(),
+            archive.projects.len(),
+            archive.tasks.len(),
+            archive.tags.len(),
+            archive.time_entries.len(),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::error::ApiError;
+    use crate::models::{Entities, User, Workspace};
+
+    #[tokio::test]
+    async fn a_failed_fetch_writes_no_archive() {
+        let mut api_client = MockApiClient::new();
+        api_client.expect_get_user().returning(|| {
+            Ok(User {
+                api_token: String::new(),
+                email: String::new(),
+                fullname: None,
+                timezone: "UTC".to_string(),
+                default_workspace_id: 1,
+                created_at: None,
+            })
+        });
+        api_client.expect_get_entities().returning(|| {
+            Ok(Entities {
+                workspaces: vec![Workspace {
+                    id: 1,
+                    name: "Acme".to_string(),
+                    admin: true,
+                }],
+                ..Entities::default()
+            })
+        });
+        api_client.expect_get_tags().returning(|_| Ok(Vec::new()));
+        api_client
+            .expect_get_time_entries_filtered()
+            .returning(|_, _| Err(Box::new(ApiError::Status(429))));
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("backup.json");
+        let result = BackupCommand::execute(api_client, path.clone(), None, false).await;
+
+        assert!(result.is_err());
+        assert!(!path.exists());
+    }
+}
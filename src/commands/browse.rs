@@ -0,0 +1,391 @@
+use crate::api::client::ApiClient;
+use crate::commands;
+use crate::config::global;
+use crate::constants;
+use crate::error::{ArgumentError, NotFoundError};
+use crate::models::{EditableTimeEntry, ResultWithDefaultError, TimeEntry};
+use crate::output::{change_message, print_change_json, ToOutput};
+use crate::parcel::Parcel;
+use crate::picker::{preview, ItemPicker, PickableItem, PickableItemKind, PickerAction};
+use crate::utilities;
+use chrono::{DateTime, NaiveTime, Utc};
+use commands::cont::ContinueCommand;
+use commands::delete::DeleteCommand;
+use commands::edit::{editor_context, EditCommand};
+use commands::start::StartCommand;
+
+/// The actions offered on the picked item, in the order of `Action`.
+const ACTIONS: [PickerAction; 6] = [
+    PickerAction {
+        key: "enter",
+        name: "continue or start",
+    },
+    PickerAction {
+        key: "ctrl-e",
+        name: "edit",
+    },
+    PickerAction {
+        key: "ctrl-x",
+        name: "delete",
+    },
+    PickerAction {
+        key: "ctrl-t",
+        name: "split",
+    },
+    PickerAction {
+        key: "ctrl-y",
+        name: "duplicate",
+    },
+    PickerAction {
+        key: "ctrl-s",
+        name: "start with same project",
+    },
+];
+
+enum Action {
+    ContinueOrStart,
+    Edit,
+    Delete,
+    Split,
+    Duplicate,
+    StartWithSameProject,
+}
+
+impl Action {
+    fn from_index(index: usize) -> Action {
+        match index {
+            1 => Action::Edit,
+            2 => Action::Delete,
+            3 => Action::Split,
+            4 => Action::Duplicate,
+            5 => Action::StartWithSameProject,
+            _ => Action::ContinueOrStart,
+        }
+    }
+}
+
+pub struct BrowseCommand;
+
+impl BrowseCommand {
+    /// Lists recent time entries, then projects and tags, and performs the
+    /// action picked with the item. Entries are continued, edited, deleted,
+    /// split or duplicated, or a new entry is started in their project;
+    /// projects and tags start a new entry with them.
+    pub async fn execute(
+        api_client: impl ApiClient,
+        picker: Box<dyn ItemPicker>,
+        workspace: Option<String>,
+        default_workspace: Option<String>,
+        yes: bool,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
+        let entities = api_client.get_entities().await?;
+        let workspace_from_flag = match &workspace {
+            Some(workspace) => Some(entities.resolve_workspace(workspace)?),
+            None => None,
+        };
+        let mut entities = match workspace_from_flag {
+            Some(workspace_id) => entities.in_workspace(workspace_id),
+            None => entities,
+        };
+        let workspace_ids: Vec<i64> = match workspace_from_flag {
+            Some(workspace_id) => vec![workspace_id],
+            None => entities.workspaces.iter().map(|w| w.id).collect(),
+        };
+        for workspace_id in workspace_ids {
+            entities
+                .tags
+                .extend(api_client.get_tags(workspace_id).await?);
+        }
+
+        let mut time_entries = entities.time_entries.clone();
+        time_entries.sort_by_key(|te| std::cmp::Reverse(te.start));
+        let mut projects: Vec<_> = entities.projects.values().cloned().collect();
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut tags = entities.tags.clone();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut items: Vec<PickableItem> = time_entries
+            .iter()
+            .map(|te| {
+                PickableItem::from_time_entry(te.clone())
+                    .with_preview(preview::time_entry(te, &entities.time_entries))
+            })
+            .collect();
+        items.extend(projects.iter().map(|p| {
+            PickableItem::from_project(p.clone())
+                .with_preview(preview::project(p, &entities.time_entries))
+        }));
+        items.extend(tags.iter().cloned().map(PickableItem::from_tag));
+        if items.is_empty() {
+            return Err(Box::new(NotFoundError::TimeEntry(None)));
+        }
+
+        let (key, action_index) = picker.pick_with_action(items, &ACTIONS)?;
+        let action = Action::from_index(action_index);
+
+        match key.kind {
+            PickableItemKind::TimeEntry => {
+                let time_entry = time_entries.into_iter().find(|te| te.id == key.id).unwrap();
+                match action {
+                    Action::ContinueOrStart => {
+                        ContinueCommand::execute(
+                            api_client,
+                            workspace,
+                            None,
+                            Some(time_entry.id),
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            json,
+                        )
+                        .await
+                    }
+                    Action::Edit => {
+                        EditCommand::execute(
+                            api_client,
+                            Some(time_entry.id),
+                            picker,
+                            false,
+                            None,
+                            None,
+                            None,
+                            false,
+                            None,
+                            None,
+                            json,
+                        )
+                        .await
+                    }
+                    Action::Delete => {
+                        DeleteCommand::execute(api_client, Some(time_entry.id), picker, yes, json)
+                            .await
+                    }
+                    Action::Split => {
+                        let end = time_entry.stop.unwrap_or_else(Utc::now);
+                        let answer = utilities::read_line_from_stderr_prompt(&format!(
+                            "Split at (HH:MM, empty for {}): ",
+                            global::local_time(midpoint(time_entry.start, end)).format("%H:%M")
+                        ));
+                        let at = parse_split_time(&time_entry, end, &answer)?;
+                        let first = time_entry.as_stopped_time_entry(at);
+                        let second = TimeEntry {
+                            start: at,
+                            created_with: Some(constants::CLIENT_NAME.to_string()),
+                            ..time_entry.clone()
+                        };
+                        // Neither order may lose time: a stopped entry is only
+                        // shortened once its second half exists, a running one
+                        // has to stop first and is restarted if that fails.
+                        let second = match time_entry.stop {
+                            Some(stop) => second.as_stopped_time_entry(stop),
+                            None => second.as_running_time_entry(at),
+                        };
+                        let id = match time_entry.stop {
+                            Some(_) => {
+                                let id = api_client.create_time_entry(second.clone()).await?;
+                                api_client.update_time_entry(first.clone()).await?;
+                                id
+                            }
+                            None => {
+                                api_client.update_time_entry(first.clone()).await?;
+                                match api_client.create_time_entry(second.clone()).await {
+                                    Ok(id) => id,
+                                    Err(error) => {
+                                        api_client.update_time_entry(time_entry.clone()).await?;
+                                        return Err(error);
+                                    }
+                                }
+                            }
+                        };
+                        let second = TimeEntry { id, ..second };
+                        if json {
+                            print_change_json(&[first.to_output(), second.to_output()]);
+                        } else {
+                            println!(
+                                "{}\n{}\n{}",
+                                change_message("Time entry split", "split time entry"),
+                                first,
+                                second
+                            );
+                        }
+                        Ok(())
+                    }
+                    Action::Duplicate => {
+                        let context = editor_context(
+                            &api_client,
+                            entities.clone(),
+                            &[time_entry.workspace_id],
+                        )
+                        .await?;
+                        let copy = EditableTimeEntry {
+                            entry: TimeEntry {
+                                created_with: Some(constants::CLIENT_NAME.to_string()),
+                                ..time_entry
+                            },
+                            context: &context,
+                        }
+                        .update_in_editor()?
+                        .entry;
+                        let id = api_client.create_time_entry(copy.clone()).await?;
+                        let duplicated = TimeEntry { id, ..copy };
+                        if json {
+                            print_change_json(&duplicated.to_output());
+                        } else {
+                            println!(
+                                "{}\n{}",
+                                change_message("Time entry duplicated", "duplicate time entry"),
+                                duplicated
+                            );
+                        }
+                        Ok(())
+                    }
+                    Action::StartWithSameProject => {
+                        start_new(
+                            api_client,
+                            picker,
+                            time_entry.workspace_id,
+                            default_workspace,
+                            time_entry.project.map(|p| p.name),
+                            None,
+                            yes,
+                            json,
+                        )
+                        .await
+                    }
+                }
+            }
+            kind => {
+                let (workspace_id, project, tags) = match kind {
+                    PickableItemKind::Tag => {
+                        let tag = tags.into_iter().find(|t| t.id == key.id).unwrap();
+                        (tag.workspace_id, None, Some(vec![tag.name]))
+                    }
+                    _ => {
+                        let project = projects.into_iter().find(|p| p.id == key.id).unwrap();
+                        (project.workspace_id, Some(project.name), None)
+                    }
+                };
+                match action {
+                    Action::ContinueOrStart | Action::StartWithSameProject => {
+                        start_new(
+                            api_client,
+                            picker,
+                            workspace_id,
+                            default_workspace,
+                            project,
+                            tags,
+                            yes,
+                            json,
+                        )
+                        .await
+                    }
+                    _ => Err(Box::new(ArgumentError::ActionNeedsTimeEntry(
+                        ACTIONS[action_index].name.to_string(),
+                    ))),
+                }
+            }
+        }
+    }
+}
+
+fn midpoint(start: DateTime<Utc>, end: DateTime<Utc>) -> DateTime<Utc> {
+    start + (end - start) / 2
+}
+
+/// Reads the split time as `HH:MM` on the day the entry started, in the
+/// display timezone. Empty means the middle of the entry. The time has to fall
+/// strictly within the entry, `end` being now for a running one.
+fn parse_split_time(
+    time_entry: &TimeEntry,
+    end: DateTime<Utc>,
+    value: &str,
+) -> ResultWithDefaultError<DateTime<Utc>> {
+    let invalid = |reason: &str| -> Box<dyn std::error::Error + Send> {
+        Box::new(ArgumentError::InvalidTimestamp(
+            "split time".to_string(),
+            value.to_string(),
+            reason.to_string(),
+        ))
+    };
+    if value.is_empty() {
+        return Ok(midpoint(time_entry.start, end));
+    }
+    let time = NaiveTime::parse_from_str(value, "%H:%M").map_err(|e| invalid(&e.to_string()))?;
+    let start = global::local_time(time_entry.start);
+    let at = start
+        .date_naive()
+        .and_time(time)
+        .and_local_timezone(*start.offset())
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+        .ok_or_else(|| invalid("not a valid local time"))?;
+    if at <= time_entry.start || at >= end {
+        return Err(invalid(
+            "must fall between the start and the end of the entry",
+        ));
+    }
+    Ok(at)
+}
+
+/// Starts a new entry in the workspace, going through `start` so that names
+/// are resolved and the running entry is stopped the usual way.
+#[allow(clippy::too_many_arguments)]
+async fn start_new(
+    api_client: impl ApiClient,
+    picker: Box<dyn ItemPicker>,
+    workspace_id: i64,
+    default_workspace: Option<String>,
+    project: Option<String>,
+    tags: Option<Vec<String>>,
+    yes: bool,
+    json: bool,
+) -> ResultWithDefaultError<()> {
+    StartCommand::execute(
+        api_client,
+        picker,
+        Some(workspace_id.to_string()),
+        default_workspace,
+        None,
+        project,
+        tags,
+        false,
+        false,
+        None,
+        false,
+        false,
+        false,
+        yes,
+        json,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn the_split_time_defaults_to_the_middle_and_must_fall_within_the_entry() {
+        let start = Utc::now() - Duration::hours(2);
+        let time_entry = TimeEntry {
+            start,
+            ..TimeEntry::default()
+        }
+        .as_stopped_time_entry(start + Duration::hours(2));
+        let end = time_entry.stop.unwrap();
+
+        assert_eq!(
+            parse_split_time(&time_entry, end, "").unwrap(),
+            start + Duration::hours(1)
+        );
+        assert!(parse_split_time(&time_entry, end, "25:00").is_err());
+        let before_start = global::local_time(start - Duration::minutes(1))
+            .format("%H:%M")
+            .to_string();
+        assert!(parse_split_time(&time_entry, end, &before_start).is_err());
+    }
+}
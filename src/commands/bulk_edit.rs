@@ -0,0 +1,144 @@
+use crate::api::client::ApiClient;
+use crate::commands::edit::editor_context;
+use crate::constants::DEFAULT_ENTITY_ID;
+use crate::models::{
+    EditableTimeEntry, EditorContext, ResultWithDefaultError, TimeEntry, TimeEntryBatch,
+};
+use crate::output::{change_message, print_change_json, print_json, BulkEditOutput, ToOutput};
+use crate::parcel::Parcel;
+use crate::utilities;
+use crate::workspace::resolve_workspace_id;
+use colored::Colorize;
+use std::collections::HashSet;
+
+pub struct BulkEditCommand;
+
+impl BulkEditCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        default_workspace: Option<String>,
+        since: Option<String>,
+        until: Option<String>,
+        yes: bool,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
+        utilities::check_date_argument("--since", since.as_deref())?;
+        utilities::check_date_argument("--until", until.as_deref())?;
+        // Only --workspace narrows the entries, new ones go to the default
+        let workspace_from_flag = match workspace {
+            Some(workspace) => Some(resolve_workspace_id(&api_client, Some(&workspace)).await?),
+            None => None,
+        };
+        let workspace_id = match workspace_from_flag {
+            Some(workspace_id) => workspace_id,
+            None => resolve_workspace_id(&api_client, default_workspace.as_deref()).await?,
+        };
+        let mut entries: Vec<TimeEntry> = api_client
+            .get_time_entries_filtered(since, until)
+            .await?
+            .into_iter()
+            .filter(|te| workspace_from_flag.is_none_or(|id| te.workspace_id == id))
+            .collect();
+        entries.sort_by_key(|te| te.start);
+
+        let mut workspace_ids: Vec<i64> = entries.iter().map(|te| te.workspace_id).collect();
+        workspace_ids.push(workspace_id);
+        let entities = api_client.get_entities().await?;
+        let context = editor_context(&api_client, entities, &workspace_ids).await?;
+        let batch = TimeEntryBatch {
+            entries,
+            workspace_id,
+            context: &context,
+        };
+        let edited = batch.update_in_editor()?;
+
+        let kept_ids: HashSet<i64> = edited.entries.iter().map(|te| te.id).collect();
+        let mut updated = Vec::new();
+        let mut created = Vec::new();
+        for entry in edited.entries {
+            if entry.id == DEFAULT_ENTITY_ID {
+                created.push(entry);
+                continue;
+            }
+            let original = batch.entries.iter().find(|te| te.id == entry.id);
+            if original.is_some_and(|original| is_changed(&context, original, &entry)) {
+                updated.push(entry);
+            }
+        }
+        let removed: Vec<&TimeEntry> = batch
+            .entries
+            .iter()
+            .filter(|original| !kept_ids.contains(&original.id))
+            .collect();
+
+        if updated.is_empty() && created.is_empty() && removed.is_empty() {
+            if json {
+                print_json(&BulkEditOutput::default());
+            } else {
+                println!("{}", "No changes".yellow());
+            }
+            return Ok(());
+        }
+
+        if !json {
+            for entry in updated.iter() {
+                println!("{} {entry}", "~".yellow().bold());
+            }
+            for entry in created.iter() {
+                println!("{} {entry}", "+".green().bold());
+            }
+            for entry in removed.iter() {
+                println!("{} {entry}", "-".red().bold());
+            }
+        }
+
+        // The summary above already lists the removed entries
+        let delete = !removed.is_empty()
+            && utilities::confirm("", &format!("Delete {} time entries?", removed.len()), yes)?;
+
+        let mut output = BulkEditOutput::default();
+        for entry in updated {
+            api_client.update_time_entry(entry.clone()).await?;
+            output.updated.push(entry.to_output());
+        }
+        for entry in created {
+            let id = api_client.create_time_entry(entry.clone()).await?;
+            output.created.push(TimeEntry { id, ..entry }.to_output());
+        }
+        if delete {
+            for entry in removed {
+                api_client
+                    .delete_time_entry(entry.workspace_id, entry.id)
+                    .await?;
+                output.deleted.push(entry.id);
+            }
+        }
+
+        if json {
+            print_change_json(&output);
+        } else {
+            println!(
+                "{} {} updated, {} created, {} deleted",
+                change_message("Time entries saved:", "save time entries:"),
+                output.updated.len(),
+                output.created.len(),
+                output.deleted.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Entries are compared through their editor rendering, which covers exactly
+/// the fields the buffer can change.
+fn is_changed(context: &EditorContext, original: &TimeEntry, edited: &TimeEntry) -> bool {
+    let render = |entry: &TimeEntry| {
+        EditableTimeEntry {
+            entry: entry.clone(),
+            context,
+        }
+        .serialize()
+    };
+    render(original) != render(edited)
+}
@@ -1,36 +1,73 @@
 use crate::api::client::ApiClient;
 use crate::commands;
+use crate::error::{ApiError, NotFoundError};
 use crate::models;
+use crate::output::{change_message, print_change_json, print_json, ToOutput};
 use crate::picker;
 use chrono::Utc;
 use colored::Colorize;
+use commands::edit::apply_flag_edits;
 use commands::stop::{StopCommand, StopCommandOrigin};
-use models::{ResultWithDefaultError, TimeEntry};
-use picker::{ItemPicker, PickableItem};
+use models::{Entities, ResultWithDefaultError, TimeEntry};
+use picker::{preview, ItemPicker, PickableItem};
 
 pub struct ContinueCommand;
 
 impl ContinueCommand {
+    /// Continues the entry with the given id, the newest entry whose
+    /// description contains `search`, a picked entry or, by default, the most
+    /// recent stopped entry. The override flags are applied to the copy.
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         api_client: impl ApiClient,
+        workspace: Option<String>,
         picker: Option<Box<dyn ItemPicker>>,
+        id: Option<i64>,
+        search: Option<String>,
+        description: Option<String>,
+        project_name: Option<String>,
+        tags: Option<Vec<String>>,
+        billable: Option<bool>,
+        json: bool,
     ) -> ResultWithDefaultError<()> {
+        let entities = api_client.get_entities().await?;
+        let entities = match workspace {
+            Some(workspace) => entities.in_workspace(entities.resolve_workspace(&workspace)?),
+            None => entities,
+        };
+        // Look the entry up before stopping anything, so a typo doesn't stop
+        // the running entry.
+        let selected_time_entry = match (id, search) {
+            (Some(id), _) => Some(find_by_id(&api_client, &entities, id).await?),
+            (None, Some(search)) => Some(find_by_description(&entities, &search)?),
+            (None, None) => None,
+        };
+
         let running_time_entry =
-            StopCommand::execute(&api_client, StopCommandOrigin::ContinueCommand).await?;
+            StopCommand::execute(&api_client, StopCommandOrigin::ContinueCommand, json).await?;
 
-        let entities = api_client.get_entities().await?;
-        if entities.time_entries.is_empty() {
-            println!("{}", "No time entries in last 90 days".red());
+        if selected_time_entry.is_none() && entities.time_entries.is_empty() {
+            if json {
+                print_json(&None::<()>);
+            } else {
+                println!("{}", "No time entries in last 90 days".red());
+            }
             return Ok(());
         }
 
-        let time_entry_to_continue = match picker {
-            None => get_first_stopped_time_entry(entities.time_entries, running_time_entry),
-            Some(time_entry_picker) => {
+        let time_entry_to_continue = match (selected_time_entry, picker) {
+            (Some(time_entry), _) => Some(time_entry),
+            (None, None) => {
+                get_first_stopped_time_entry(entities.time_entries.clone(), running_time_entry)
+            }
+            (None, Some(time_entry_picker)) => {
                 let pickable_items = entities
                     .time_entries
                     .iter()
-                    .map(|te| PickableItem::from_time_entry(te.clone()))
+                    .map(|te| {
+                        PickableItem::from_time_entry(te.clone())
+                            .with_preview(preview::time_entry(te, &entities.time_entries))
+                    })
                     .collect();
                 let picked_key = time_entry_picker.pick(pickable_items)?;
                 let picked_time_entry = entities
@@ -43,22 +80,40 @@ impl ContinueCommand {
         };
 
         match time_entry_to_continue {
+            None if json => print_json(&None::<()>),
             None => println!("{}", "No time entry to continue".red()),
             Some(time_entry) => {
+                let time_entry = apply_flag_edits(
+                    &entities,
+                    time_entry,
+                    description,
+                    project_name,
+                    tags,
+                    None,
+                    None,
+                )?;
+                let time_entry = TimeEntry {
+                    billable: billable.unwrap_or(time_entry.billable),
+                    ..time_entry
+                };
                 let start_time = Utc::now();
                 let time_entry_to_create = time_entry.as_running_time_entry(start_time);
-                let continued_entry_id = api_client.create_time_entry(time_entry_to_create).await?;
-                let entities = api_client.get_entities().await?;
-                let continued_entry = entities
-                    .time_entries
-                    .iter()
-                    .find(|te| te.id == continued_entry_id)
-                    .unwrap();
-                println!(
-                    "{}\n{}",
-                    "Time entry continued successfully".green(),
-                    continued_entry
-                )
+                let continued_entry_id = api_client
+                    .create_time_entry(time_entry_to_create.clone())
+                    .await?;
+                let continued_entry = TimeEntry {
+                    id: continued_entry_id,
+                    ..time_entry_to_create
+                };
+                if json {
+                    print_change_json(&continued_entry.to_output());
+                } else {
+                    println!(
+                        "{}\n{}",
+                        change_message("Time entry continued successfully", "continue time entry"),
+                        continued_entry
+                    )
+                }
             }
         }
 
@@ -66,14 +121,181 @@ impl ContinueCommand {
     }
 }
 
+/// The entry with `id`, fetched on its own when it is older than the
+/// entries `entities` holds.
+async fn find_by_id(
+    api_client: &impl ApiClient,
+    entities: &Entities,
+    id: i64,
+) -> ResultWithDefaultError<TimeEntry> {
+    if let Some(time_entry) = entities.time_entries.iter().find(|te| te.id == id) {
+        return Ok(time_entry.clone());
+    }
+    match api_client.get_time_entry(id).await {
+        Err(error) if matches!(error.downcast_ref::<ApiError>(), Some(ApiError::NotFound)) => {
+            Err(Box::new(NotFoundError::TimeEntry(Some(id))))
+        }
+        result => result,
+    }
+}
+
+/// The newest entry whose description contains `search`, ignoring case.
+fn find_by_description(entities: &Entities, search: &str) -> ResultWithDefaultError<TimeEntry> {
+    let search = search.to_lowercase();
+    entities
+        .time_entries
+        .iter()
+        .filter(|te| te.description.to_lowercase().contains(&search))
+        .max_by_key(|te| te.start)
+        .cloned()
+        .ok_or_else(|| -> Box<dyn std::error::Error + Send> {
+            Box::new(NotFoundError::TimeEntry(None))
+        })
+}
+
 fn get_first_stopped_time_entry(
     time_entries: Vec<TimeEntry>,
     running_time_entry: Option<TimeEntry>,
 ) -> Option<TimeEntry> {
     // Don't continue a running entry that was just stopped.
-    let continue_entry_index = match running_time_entry {
-        None => 0,
-        Some(_) => 1,
-    };
-    time_entries.get(continue_entry_index).cloned()
+    let stopped_entry_id = running_time_entry.map(|te| te.id);
+    time_entries
+        .into_iter()
+        .find(|te| Some(te.id) != stopped_entry_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::models::{Project, Task};
+    use chrono::Duration;
+
+    fn project(id: i64, name: &str) -> Project {
+        Project {
+            id,
+            name: name.to_string(),
+            workspace_id: 1,
+            client: None,
+            is_private: false,
+            active: true,
+            at: Utc::now(),
+            created_at: Utc::now(),
+            color: "#06aaf5".to_string(),
+            billable: None,
+        }
+    }
+
+    #[test]
+    fn match_picks_the_newest_entry_ignoring_case() {
+        let now = Utc::now();
+        let entry = |id: i64, description: &str, hours_ago: i64| TimeEntry {
+            id,
+            description: description.to_string(),
+            start: now - Duration::hours(hours_ago),
+            ..TimeEntry::default()
+        };
+        let entities = Entities {
+            time_entries: vec![
+                entry(1, "Daily standup", 2),
+                entry(2, "Review", 1),
+                entry(3, "STANDUP notes", 3),
+            ],
+            ..Entities::default()
+        };
+
+        assert_eq!(find_by_description(&entities, "standup").unwrap().id, 1);
+        assert!(find_by_description(&entities, "retro").is_err());
+    }
+
+    #[tokio::test]
+    async fn a_new_project_drops_the_task_of_the_continued_entry() {
+        let website = project(1, "Website");
+        let backend = project(2, "Backend");
+        let entry = TimeEntry {
+            id: 10,
+            description: "Landing page".to_string(),
+            project: Some(website.clone()),
+            task: Some(Task {
+                id: 5,
+                name: "Design".to_string(),
+                workspace_id: 1,
+                project: website.clone(),
+            }),
+            ..TimeEntry::default()
+        }
+        .as_stopped_time_entry(Utc::now());
+        let entities = Entities {
+            time_entries: vec![entry],
+            projects: [(1, website), (2, backend)].into_iter().collect(),
+            ..Entities::default()
+        };
+
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_entities()
+            .returning(move || Ok(entities.clone()));
+        api_client
+            .expect_create_time_entry()
+            .withf(|te| te.task.is_none() && te.project.as_ref().is_some_and(|p| p.id == 2))
+            .times(1)
+            .returning(|_| Ok(11));
+
+        ContinueCommand::execute(
+            api_client,
+            None,
+            None,
+            Some(10),
+            None,
+            None,
+            Some("Backend".to_string()),
+            None,
+            None,
+            true,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn an_entry_older_than_the_fetched_ones_is_continued_by_id() {
+        let old_entry = TimeEntry {
+            id: 3,
+            description: "Archive cleanup".to_string(),
+            billable: true,
+            start: Utc::now() - Duration::days(200),
+            ..TimeEntry::default()
+        }
+        .as_stopped_time_entry(Utc::now() - Duration::days(200) + Duration::hours(1));
+
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_entities()
+            .returning(|| Ok(Entities::default()));
+        api_client
+            .expect_get_time_entry()
+            .withf(|id| *id == 3)
+            .times(1)
+            .returning(move |_| Ok(old_entry.clone()));
+        api_client
+            .expect_create_time_entry()
+            .withf(|te| te.description == "Archive cleanup" && !te.billable)
+            .times(1)
+            .returning(|_| Ok(11));
+
+        ContinueCommand::execute(
+            api_client,
+            None,
+            None,
+            Some(3),
+            None,
+            None,
+            None,
+            None,
+            Some(false),
+            true,
+        )
+        .await
+        .unwrap();
+    }
 }
@@ -1,19 +1,30 @@
 use crate::api::client::ApiClient;
 use crate::models::ResultWithDefaultError;
-use colored::Colorize;
+use crate::output::{change_message, print_change_json, ToOutput};
+use crate::workspace::resolve_workspace_id;
 
 pub struct CreateProjectCommand;
 
 impl CreateProjectCommand {
     pub async fn execute(
         api_client: impl ApiClient,
+        workspace: Option<String>,
         name: String,
         color: String,
+        json: bool,
     ) -> ResultWithDefaultError<()> {
-        let workspace_id = api_client.get_user().await?.default_workspace_id;
-        match api_client.create_project(workspace_id, name, color).await {
-            Err(error) => println!("{}\n{}", "Couldn't create project".red(), error),
-            Ok(project) => println!("{}\n{}", "Project created successfully".green(), project),
+        let workspace_id = resolve_workspace_id(&api_client, workspace.as_deref()).await?;
+        let project = api_client
+            .create_project(workspace_id, name, color, None)
+            .await?;
+        if json {
+            print_change_json(&project.to_output());
+        } else {
+            println!(
+                "{}\n{}",
+                change_message("Project created successfully", "create project"),
+                project
+            );
         }
         Ok(())
     }
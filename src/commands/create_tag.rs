@@ -1,15 +1,27 @@
 use crate::api::client::ApiClient;
 use crate::models::ResultWithDefaultError;
-use colored::Colorize;
+use crate::output::{change_message, print_change_json, ToOutput};
+use crate::workspace::resolve_workspace_id;
 
 pub struct CreateTagCommand;
 
 impl CreateTagCommand {
-    pub async fn execute(api_client: impl ApiClient, name: String) -> ResultWithDefaultError<()> {
-        let workspace_id = api_client.get_user().await?.default_workspace_id;
-        match api_client.create_tag(workspace_id, name).await {
-            Err(error) => println!("{}\n{}", "Couldn't create tag".red(), error),
-            Ok(tag) => println!("{}\n{}", "Tag created successfully".green(), tag),
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = resolve_workspace_id(&api_client, workspace.as_deref()).await?;
+        let tag = api_client.create_tag(workspace_id, name).await?;
+        if json {
+            print_change_json(&tag.to_output());
+        } else {
+            println!(
+                "{}\n{}",
+                change_message("Tag created successfully", "create tag"),
+                tag
+            );
         }
         Ok(())
     }
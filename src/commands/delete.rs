@@ -1,22 +1,130 @@
 use crate::api::client::ApiClient;
-use crate::models::ResultWithDefaultError;
+use crate::error::NotFoundError;
+use crate::models::{ResultWithDefaultError, TimeEntry};
+use crate::output::{change_message, print_change_json, print_json, DeletedOutput};
+use crate::picker::{preview, ItemPicker, PickableItem};
+use crate::utilities;
 use colored::Colorize;
 
 pub struct DeleteCommand;
 
 impl DeleteCommand {
-    pub async fn execute(api_client: impl ApiClient, id: i64) -> ResultWithDefaultError<()> {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        id: Option<i64>,
+        picker: Box<dyn ItemPicker>,
+        yes: bool,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
         let entities = api_client.get_entities().await?;
-        let time_entry = entities.time_entries.iter().find(|te| te.id == id).cloned();
-
-        match time_entry {
-            None => println!("{}", format!("No time entry found with id {id}").yellow()),
-            Some(entry) => match api_client.delete_time_entry(entry.workspace_id, id).await {
-                Err(error) => println!("{}\n{}", "Couldn't delete time entry".red(), error),
-                Ok(()) => println!("{}\n{}", "Time entry deleted successfully".green(), entry),
-            },
+        let id = match id {
+            Some(id) => id,
+            None => {
+                return Self::delete_picked(&api_client, entities.time_entries, picker, yes, json)
+                    .await
+            }
+        };
+        let entry = entities
+            .time_entries
+            .iter()
+            .find(|te| te.id == id)
+            .cloned()
+            .ok_or_else(|| -> Box<dyn std::error::Error + Send> {
+                Box::new(NotFoundError::TimeEntry(Some(id)))
+            })?;
+
+        if !utilities::confirm(
+            &format!("{} {entry}", "-".red().bold()),
+            "Delete this time entry?",
+            yes,
+        )? {
+            if json {
+                print_json(&None::<()>);
+            } else {
+                println!("{}", "Nothing deleted".yellow());
+            }
+            return Ok(());
+        }
+
+        api_client.delete_time_entry(entry.workspace_id, id).await?;
+        if json {
+            print_change_json(&DeletedOutput::new("time_entry", id));
+        } else {
+            println!(
+                "{}\n{}",
+                change_message("Time entry deleted successfully", "delete time entry"),
+                entry
+            );
         }
 
         Ok(())
     }
+
+    /// Lets the user pick any number of entries and deletes them once the
+    /// summary has been confirmed.
+    async fn delete_picked(
+        api_client: &impl ApiClient,
+        time_entries: Vec<TimeEntry>,
+        picker: Box<dyn ItemPicker>,
+        yes: bool,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
+        if time_entries.is_empty() {
+            return Err(Box::new(NotFoundError::TimeEntry(None)));
+        }
+        let pickable_items = time_entries
+            .iter()
+            .map(|te| {
+                PickableItem::from_time_entry(te.clone())
+                    .with_preview(preview::time_entry(te, &time_entries))
+            })
+            .collect();
+        let picked_ids: Vec<i64> = picker
+            .pick_many(pickable_items, &[])?
+            .keys
+            .iter()
+            .map(|key| key.id)
+            .collect();
+        if picked_ids.is_empty() {
+            return Err(Box::new(NotFoundError::TimeEntry(None)));
+        }
+        let picked: Vec<TimeEntry> = time_entries
+            .into_iter()
+            .filter(|te| picked_ids.contains(&te.id))
+            .collect();
+
+        let summary = picked
+            .iter()
+            .map(|entry| format!("{} {entry}", "-".red().bold()))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let confirmed = utilities::confirm(
+            &summary,
+            &format!("Delete {} time entries?", picked.len()),
+            yes,
+        )?;
+
+        let mut deleted = Vec::new();
+        if confirmed {
+            for entry in picked.iter() {
+                api_client
+                    .delete_time_entry(entry.workspace_id, entry.id)
+                    .await?;
+                deleted.push(DeletedOutput::new("time_entry", entry.id));
+            }
+        }
+
+        if json {
+            print_change_json(&deleted);
+        } else if confirmed {
+            println!(
+                "{} {}",
+                change_message("Time entries deleted successfully:", "delete time entries:"),
+                deleted.len()
+            );
+        } else {
+            println!("{}", "Nothing deleted".yellow());
+        }
+        Ok(())
+    }
 }
@@ -1,29 +1,64 @@
 use crate::api::client::ApiClient;
+use crate::error::NotFoundError;
 use crate::models::ResultWithDefaultError;
+use crate::output::{change_message, print_change_json, print_json, DeletedOutput};
+use crate::utilities;
 use colored::Colorize;
 
 pub struct DeleteProjectCommand;
 
 impl DeleteProjectCommand {
-    pub async fn execute(api_client: impl ApiClient, name: String) -> ResultWithDefaultError<()> {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+        yes: bool,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
         let entities = api_client.get_entities().await?;
+        let entities = match workspace {
+            Some(workspace) => entities.in_workspace(entities.resolve_workspace(&workspace)?),
+            None => entities,
+        };
 
-        let project = entities.projects.values().find(|p| p.name == name).cloned();
+        let project = entities
+            .projects
+            .values()
+            .find(|p| p.name == name)
+            .cloned()
+            .ok_or_else(|| -> Box<dyn std::error::Error + Send> {
+                Box::new(NotFoundError::Project(name.clone()))
+            })?;
 
-        match project {
-            None => println!(
-                "{}",
-                format!("No project found with name '{name}'").yellow()
-            ),
-            Some(project) => {
-                match api_client
-                    .delete_project(project.workspace_id, project.id)
-                    .await
-                {
-                    Err(error) => println!("{}\n{}", "Couldn't delete project".red(), error),
-                    Ok(()) => println!("{}\n{}", "Project deleted successfully".green(), project),
-                }
+        let referencing_entries = entities
+            .time_entries
+            .iter()
+            .filter(|te| te.project.as_ref().is_some_and(|p| p.id == project.id))
+            .count();
+        let summary = format!(
+            "{} {project}\n{referencing_entries} time entries from the last 90 days reference it",
+            "-".red().bold()
+        );
+        if !utilities::confirm(&summary, "Delete this project?", yes)? {
+            if json {
+                print_json(&None::<()>);
+            } else {
+                println!("{}", "Nothing deleted".yellow());
             }
+            return Ok(());
+        }
+
+        api_client
+            .delete_project(project.workspace_id, project.id)
+            .await?;
+        if json {
+            print_change_json(&DeletedOutput::new("project", project.id));
+        } else {
+            println!(
+                "{}\n{}",
+                change_message("Project deleted successfully", "delete project"),
+                project
+            );
         }
 
         Ok(())
@@ -1,22 +1,57 @@
 use crate::api::client::ApiClient;
+use crate::error::NotFoundError;
 use crate::models::ResultWithDefaultError;
+use crate::output::{change_message, print_change_json, print_json, DeletedOutput};
+use crate::utilities;
+use crate::workspace::resolve_workspace_id;
 use colored::Colorize;
 
 pub struct DeleteTagCommand;
 
 impl DeleteTagCommand {
-    pub async fn execute(api_client: impl ApiClient, name: String) -> ResultWithDefaultError<()> {
-        let workspace_id = api_client.get_user().await?.default_workspace_id;
+    pub async fn execute(
+        api_client: impl ApiClient,
+        workspace: Option<String>,
+        name: String,
+        yes: bool,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
+        let workspace_id = resolve_workspace_id(&api_client, workspace.as_deref()).await?;
         let tags = api_client.get_tags(workspace_id).await?;
 
-        let tag = tags.into_iter().find(|t| t.name == name);
+        let tag = tags.into_iter().find(|t| t.name == name).ok_or_else(
+            || -> Box<dyn std::error::Error + Send> { Box::new(NotFoundError::Tag(name.clone())) },
+        )?;
 
-        match tag {
-            None => println!("{}", format!("No tag found with name '{name}'").yellow()),
-            Some(tag) => match api_client.delete_tag(workspace_id, tag.id).await {
-                Err(error) => println!("{}\n{}", "Couldn't delete tag".red(), error),
-                Ok(()) => println!("{}", "Tag deleted successfully".green()),
-            },
+        let tagged_entries = api_client
+            .get_entities()
+            .await?
+            .time_entries
+            .iter()
+            .filter(|te| te.workspace_id == workspace_id && te.tags.contains(&tag.name))
+            .count();
+        let summary = format!(
+            "{} {}\n{tagged_entries} time entries from the last 90 days are tagged with it",
+            "-".red().bold(),
+            tag.name
+        );
+        if !utilities::confirm(&summary, "Delete this tag?", yes)? {
+            if json {
+                print_json(&None::<()>);
+            } else {
+                println!("{}", "Nothing deleted".yellow());
+            }
+            return Ok(());
+        }
+
+        api_client.delete_tag(workspace_id, tag.id).await?;
+        if json {
+            print_change_json(&DeletedOutput::new("tag", tag.id));
+        } else {
+            println!(
+                "{}",
+                change_message("Tag deleted successfully", "delete tag")
+            );
         }
 
         Ok(())
@@ -1,9 +1,15 @@
 use crate::api::client::ApiClient;
-use crate::constants::DEFAULT_ENTITY_ID;
-use crate::models::{Entities, ResultWithDefaultError, TimeEntry};
+use crate::config;
+use crate::error::{ArgumentError, NotFoundError};
+use crate::models::{
+    EditableTimeEntry, EditorContext, Entities, ResultWithDefaultError, TimeEntry,
+};
+use crate::output::{change_message, print_change_json, ToOutput};
 use crate::parcel::Parcel;
+use crate::picker;
+use crate::picker::{preview, ItemPicker, PickableItem};
 use chrono::{DateTime, Utc};
-use colored::Colorize;
+use chrono_tz::Tz;
 
 pub struct EditCommand;
 
@@ -12,19 +18,36 @@ impl EditCommand {
     pub async fn execute(
         api_client: impl ApiClient,
         id: Option<i64>,
+        picker: Box<dyn ItemPicker>,
+        interactive: bool,
         description: Option<String>,
         project_name: Option<String>,
         tags: Option<Vec<String>>,
+        pick_tags: bool,
         start_time: Option<String>,
         stop_time: Option<String>,
+        json: bool,
     ) -> ResultWithDefaultError<()> {
         let entities = api_client.get_entities().await?;
-        let entry = match select_entry(&entities, id) {
-            Some(entry) => entry,
-            None => {
-                println!("{}", "No matching time entry found".yellow());
-                return Ok(());
-            }
+        let id = if interactive {
+            Some(pick_entry(&entities, picker.as_ref())?)
+        } else {
+            id
+        };
+        let entry =
+            select_entry(&entities, id).ok_or_else(|| -> Box<dyn std::error::Error + Send> {
+                Box::new(NotFoundError::TimeEntry(id))
+            })?;
+
+        let tags = if pick_tags {
+            let known_tags = api_client.get_tags(entry.workspace_id).await?;
+            Some(picker::pick_tags(
+                picker.as_ref(),
+                &known_tags,
+                &entry.tags,
+            )?)
+        } else {
+            tags
         };
 
         let has_flag_edits = description.is_some()
@@ -44,12 +67,24 @@ impl EditCommand {
                 stop_time,
             )?
         } else {
-            apply_editor_edits(&entities, entry)?
+            let context = editor_context(&api_client, entities, &[entry.workspace_id]).await?;
+            EditableTimeEntry {
+                entry,
+                context: &context,
+            }
+            .update_in_editor()?
+            .entry
         };
 
-        match api_client.update_time_entry(updated.clone()).await {
-            Err(error) => println!("{}\n{}", "Couldn't update time entry".red(), error),
-            Ok(_) => println!("{}\n{}", "Time entry updated successfully".green(), updated),
+        api_client.update_time_entry(updated.clone()).await?;
+        if json {
+            print_change_json(&updated.to_output());
+        } else {
+            println!(
+                "{}\n{}",
+                change_message("Time entry updated successfully", "update time entry"),
+                updated
+            );
         }
         Ok(())
     }
@@ -68,18 +103,37 @@ fn select_entry(entities: &Entities, id: Option<i64>) -> Option<TimeEntry> {
     })
 }
 
+fn pick_entry(entities: &Entities, picker: &dyn ItemPicker) -> ResultWithDefaultError<i64> {
+    if entities.time_entries.is_empty() {
+        return Err(Box::new(NotFoundError::TimeEntry(None)));
+    }
+    let pickable_items = entities
+        .time_entries
+        .iter()
+        .map(|te| {
+            PickableItem::from_time_entry(te.clone())
+                .with_preview(preview::time_entry(te, &entities.time_entries))
+        })
+        .collect();
+    Ok(picker.pick(pickable_items)?.id)
+}
+
 fn parse_timestamp(field: &str, value: &str) -> ResultWithDefaultError<DateTime<Utc>> {
     DateTime::parse_from_rfc3339(value)
         .map(|dt| dt.with_timezone(&Utc))
         .map_err(|e| -> Box<dyn std::error::Error + Send> {
-            Box::new(std::io::Error::other(format!(
-                "{field} \"{value}\" is not a valid RFC3339 timestamp: {e}"
-            )))
+            Box::new(ArgumentError::InvalidTimestamp(
+                field.to_string(),
+                value.to_string(),
+                e.to_string(),
+            ))
         })
 }
 
+/// Applies the edit flags to a copy of the entry. Empty values remove the
+/// project, clear the tags or mark the entry running again.
 #[allow(clippy::too_many_arguments)]
-fn apply_flag_edits(
+pub fn apply_flag_edits(
     entities: &Entities,
     entry: TimeEntry,
     description: Option<String>,
@@ -93,13 +147,16 @@ fn apply_flag_edits(
         Some(name) => match entities.projects.values().find(|p| p.name == name).cloned() {
             Some(project) => Some(project),
             None => {
-                return Err(Box::new(std::io::Error::other(format!(
-                    "Project \"{name}\" not found"
-                ))));
+                return Err(Box::new(NotFoundError::Project(name.to_string())));
             }
         },
         None => entry.project.clone(),
     };
+    // A task belongs to its project, so it doesn't survive a project change
+    let task = entry
+        .task
+        .clone()
+        .filter(|task| project.as_ref().is_some_and(|p| p.id == task.project.id));
 
     let tags = match tags {
         Some(ref t) if t.len() == 1 && t[0].is_empty() => Vec::new(),
@@ -126,6 +183,7 @@ fn apply_flag_edits(
     Ok(TimeEntry {
         description: description.unwrap_or(entry.description.clone()),
         project,
+        task,
         tags,
         start,
         stop,
@@ -134,52 +192,26 @@ fn apply_flag_edits(
     })
 }
 
-fn apply_editor_edits(entities: &Entities, entry: TimeEntry) -> ResultWithDefaultError<TimeEntry> {
-    let edited = entry.update_in_editor()?;
-
-    let project = match edited.project {
-        Some(p) if p.id != DEFAULT_ENTITY_ID => Some(p),
-        Some(p) => Some(
-            entities
-                .project_for_name(edited.workspace_id, &p.name)
-                .ok_or_else(|| -> Box<dyn std::error::Error + Send> {
-                    Box::new(std::io::Error::other(format!(
-                        "Project \"{}\" not found",
-                        p.name
-                    )))
-                })?,
-        ),
-        None => None,
-    };
-
-    let task = match edited.task {
-        Some(t) if t.id != DEFAULT_ENTITY_ID => Some(t),
-        Some(t) => {
-            let project_id = project.as_ref().map(|p| p.id).ok_or_else(
-                || -> Box<dyn std::error::Error + Send> {
-                    Box::new(std::io::Error::other(format!(
-                        "Task \"{}\" requires a project",
-                        t.name
-                    )))
-                },
-            )?;
-            Some(
-                entities
-                    .task_for_name(edited.workspace_id, project_id, &t.name)
-                    .ok_or_else(|| -> Box<dyn std::error::Error + Send> {
-                        Box::new(std::io::Error::other(format!(
-                            "Task \"{}\" not found in the resolved project",
-                            t.name
-                        )))
-                    })?,
-            )
-        }
-        None => None,
-    };
-
-    Ok(TimeEntry {
-        project,
-        task,
-        ..edited
+/// Collects what the editor buffer needs: the user's timezone and the tags of
+/// the given workspaces next to the already fetched entities.
+pub async fn editor_context(
+    api_client: &impl ApiClient,
+    mut entities: Entities,
+    workspace_ids: &[i64],
+) -> ResultWithDefaultError<EditorContext> {
+    let user = api_client.get_user().await?;
+    let mut workspace_ids = workspace_ids.to_vec();
+    workspace_ids.sort_unstable();
+    workspace_ids.dedup();
+    for workspace_id in workspace_ids {
+        entities
+            .tags
+            .extend(api_client.get_tags(workspace_id).await?);
+    }
+    Ok(EditorContext {
+        timezone: config::global::get()
+            .timezone()
+            .unwrap_or_else(|| user.timezone.parse::<Tz>().unwrap_or(Tz::UTC)),
+        entities,
     })
 }
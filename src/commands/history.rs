@@ -0,0 +1,50 @@
+use crate::config;
+use crate::journal::Journal;
+use crate::models::ResultWithDefaultError;
+use crate::output::{print_json, ToOutput};
+use colored::Colorize;
+
+pub struct HistoryCommand;
+
+impl HistoryCommand {
+    pub async fn execute(
+        journal: Journal,
+        count: Option<usize>,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
+        let entries = journal.read()?;
+        let entries = entries
+            .iter()
+            .rev()
+            .take(count.unwrap_or(usize::MAX))
+            .collect::<Vec<_>>();
+
+        if json {
+            print_json(
+                &entries
+                    .iter()
+                    .map(|entry| entry.to_output())
+                    .collect::<Vec<_>>(),
+            );
+            return Ok(());
+        }
+        if entries.is_empty() {
+            println!("{}", "No operations recorded".yellow());
+            return Ok(());
+        }
+        for entry in entries {
+            println!(
+                "{} {} {}{}",
+                format!("#{}", entry.id).bold(),
+                config::global::local_time(entry.at).format("%Y-%m-%d %H:%M"),
+                entry.operation,
+                if entry.undone {
+                    " (undone)".yellow().to_string()
+                } else {
+                    "".to_string()
+                }
+            );
+        }
+        Ok(())
+    }
+}
@@ -1,139 +1,176 @@
 use crate::api;
 use crate::arguments::Entity;
 use crate::models;
+use crate::output::ToOutput;
+use crate::utilities;
+use crate::workspace::resolve_workspace_id;
 use api::client::ApiClient;
 use colored::Colorize;
-use models::ResultWithDefaultError;
+use models::{Entities, ResultWithDefaultError, Tag};
+use serde::Serialize;
+use std::fmt::Display;
 use std::io::{self, BufWriter, Write};
 
 pub struct ListCommand;
 
+/// A listed item together with the name of the workspace it belongs to, used
+/// when listing across all workspaces.
+#[derive(Serialize)]
+struct WorkspaceRow<'a, T: Serialize> {
+    workspace: &'a str,
+    #[serde(flatten)]
+    item: T,
+}
+
 impl ListCommand {
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         api_client: impl ApiClient,
+        workspace: Option<String>,
+        all_workspaces: bool,
         count: Option<usize>,
-        json_flag: bool,
+        json: bool,
         since: Option<String>,
         until: Option<String>,
         entity: Option<Entity>,
     ) -> ResultWithDefaultError<()> {
-        if let Some(Entity::Tag { json: entity_json }) = entity {
-            let json = json_flag || entity_json;
-            let user = api_client.get_user().await?;
-            match api_client.get_tags(user.default_workspace_id).await {
-                Err(error) => println!("{}\n{}", "Couldn't fetch tags from API".red(), error),
-                Ok(tags) => {
-                    let stdout = io::stdout();
-                    let mut handle = BufWriter::new(stdout);
-                    let tags = tags
-                        .iter()
-                        .take(count.unwrap_or(usize::MAX))
-                        .collect::<Vec<_>>();
-                    if json {
-                        let json_string = serde_json::to_string_pretty(&tags)
-                            .expect("failed to serialize tags to JSON");
-                        writeln!(handle, "{json_string}").expect("failed to print");
-                    } else {
-                        tags.iter()
-                            .for_each(|tag| writeln!(handle, "{tag}").expect("failed to print"));
-                    }
+        utilities::check_date_argument("--since", since.as_deref())?;
+        utilities::check_date_argument("--until", until.as_deref())?;
+        let count = count.unwrap_or(usize::MAX);
+
+        if let Some(Entity::Tag) = entity {
+            if all_workspaces {
+                let entities = api_client.get_entities().await?;
+                let mut tags: Vec<Tag> = Vec::new();
+                for workspace in entities.workspaces.iter() {
+                    tags.extend(api_client.get_tags(workspace.id).await?);
                 }
+                let rows = tags.iter().take(count).collect::<Vec<_>>();
+                print_rows(&rows, json, Some(&entities), |tag| tag.workspace_id);
+                return Ok(());
             }
+
+            let workspace_id = resolve_workspace_id(&api_client, workspace.as_deref()).await?;
+            let tags = api_client.get_tags(workspace_id).await?;
+            let rows = tags.iter().take(count).collect::<Vec<_>>();
+            print_rows(&rows, json, None, |tag| tag.workspace_id);
             return Ok(());
         }
 
-        let is_time_entry = matches!(entity, None | Some(Entity::TimeEntry { .. }));
+        let is_time_entry = matches!(entity, None | Some(Entity::TimeEntry));
         let has_date_filter = since.is_some() || until.is_some();
 
         if is_time_entry && has_date_filter {
-            let stdout = io::stdout();
-            let mut handle = BufWriter::new(stdout);
-            let json = match &entity {
-                Some(Entity::TimeEntry { json }) => json_flag || *json,
-                _ => json_flag,
+            let labels = if all_workspaces {
+                Some(api_client.get_entities().await?)
+            } else {
+                None
             };
-            match api_client.get_time_entries_filtered(since, until).await {
-                Err(error) => println!(
-                    "{}\n{}",
-                    "Couldn't fetch time entries from API".red(),
-                    error
-                ),
-                Ok(entries) => {
-                    let entries = entries
-                        .iter()
-                        .take(count.unwrap_or(usize::MAX))
-                        .collect::<Vec<_>>();
-                    if json {
-                        let json_string = serde_json::to_string_pretty(&entries)
-                            .expect("failed to serialize time entries to JSON");
-                        writeln!(handle, "{json_string}").expect("failed to print");
-                    } else {
-                        entries
-                            .iter()
-                            .for_each(|te| writeln!(handle, "{te}").expect("failed to print"));
-                    }
+            let workspace_id = match workspace {
+                Some(workspace) if !all_workspaces => {
+                    Some(resolve_workspace_id(&api_client, Some(workspace.as_str())).await?)
                 }
-            }
+                _ => None,
+            };
+            let entries = api_client.get_time_entries_filtered(since, until).await?;
+            let entries = entries
+                .iter()
+                .filter(|te| workspace_id.is_none_or(|id| te.workspace_id == id))
+                .take(count)
+                .collect::<Vec<_>>();
+            print_rows(&entries, json, labels.as_ref(), |te| te.workspace_id);
             return Ok(());
         }
 
-        match api_client.get_entities().await {
-            Err(error) => println!(
-                "{}\n{}",
-                "Couldn't fetch time entries the from API".red(),
-                error
-            ),
-            Ok(entities) => {
-                // use this to avoid calling println! in a loop:
-                // <https://rust-cli.github.io/book/tutorial/output.html#a-note-on-printing-performance>
-                let stdout = io::stdout();
-                let mut handle = BufWriter::new(stdout);
+        let entities = api_client.get_entities().await?;
+        let entities = match workspace {
+            Some(workspace) if !all_workspaces => {
+                entities.in_workspace(entities.resolve_workspace(&workspace)?)
+            }
+            _ => entities,
+        };
+        let labels = if all_workspaces {
+            Some(&entities)
+        } else {
+            None
+        };
 
-                // TODO: better error handling for writeln!
-                match entity.unwrap_or(Entity::TimeEntry { json: false }) {
-                    Entity::TimeEntry { json: entity_json } => {
-                        let json = json_flag || entity_json;
-                        let entries = entities
-                            .time_entries
-                            .iter()
-                            .take(count.unwrap_or(usize::MAX))
-                            .collect::<Vec<_>>();
+        match entity.unwrap_or(Entity::TimeEntry) {
+            Entity::TimeEntry => {
+                let entries = entities.time_entries.iter().take(count).collect::<Vec<_>>();
+                print_rows(&entries, json, labels, |te| te.workspace_id);
+            }
 
-                        if json {
-                            let json_string = serde_json::to_string_pretty(&entries)
-                                .expect("failed to serialize time entries to JSON");
-                            writeln!(handle, "{json_string}").expect("failed to print");
-                        } else {
-                            entries.iter().for_each(|time_entry| {
-                                writeln!(handle, "{time_entry}").expect("failed to print")
-                            });
-                        }
-                    }
+            Entity::Project => {
+                let projects = entities.projects.values().take(count).collect::<Vec<_>>();
+                print_rows(&projects, json, labels, |project| project.workspace_id);
+            }
 
-                    Entity::Project { json: entity_json } => {
-                        let json = json_flag || entity_json;
-                        let projects = entities
-                            .projects
-                            .values()
-                            .take(count.unwrap_or(usize::MAX))
-                            .collect::<Vec<_>>();
+            // Already handled above, but needed for exhaustive match
+            Entity::Tag => unreachable!(),
+        };
+        Ok(())
+    }
+}
 
-                        if json {
-                            let json_string = serde_json::to_string_pretty(&projects)
-                                .expect("failed to serialize projects to JSON");
-                            writeln!(handle, "{json_string}").expect("failed to print");
-                        } else {
-                            projects.iter().for_each(|project| {
-                                writeln!(handle, "{project}").expect("failed to print")
-                            });
-                        }
-                    }
+/// Prints the rows as text or JSON. When `labels` is given every row is
+/// prefixed with the name of its workspace.
+fn print_rows<T: ToOutput + Display>(
+    rows: &[&T],
+    json: bool,
+    labels: Option<&Entities>,
+    workspace_id: impl Fn(&T) -> i64,
+) {
+    // use this to avoid calling println! in a loop:
+    // <https://rust-cli.github.io/book/tutorial/output.html#a-note-on-printing-performance>
+    let stdout = io::stdout();
+    let mut handle = BufWriter::new(stdout);
 
-                    // Already handled above, but needed for exhaustive match
-                    Entity::Tag { .. } => unreachable!(),
-                };
+    // TODO: better error handling for writeln!
+    match labels {
+        None => {
+            if json {
+                let rows = rows.iter().map(|row| row.to_output()).collect::<Vec<_>>();
+                let json_string =
+                    serde_json::to_string_pretty(&rows).expect("failed to serialize to JSON");
+                writeln!(handle, "{json_string}").expect("failed to print");
+            } else {
+                rows.iter()
+                    .for_each(|row| writeln!(handle, "{row}").expect("failed to print"));
+            }
+        }
+        Some(entities) => {
+            if json {
+                let rows = rows
+                    .iter()
+                    .map(|row| WorkspaceRow {
+                        workspace: workspace_name(entities, workspace_id(row)),
+                        item: row.to_output(),
+                    })
+                    .collect::<Vec<_>>();
+                let json_string =
+                    serde_json::to_string_pretty(&rows).expect("failed to serialize to JSON");
+                writeln!(handle, "{json_string}").expect("failed to print");
+            } else {
+                rows.iter().for_each(|row| {
+                    writeln!(
+                        handle,
+                        "{} {}",
+                        format!("[{}]", workspace_name(entities, workspace_id(row))).cyan(),
+                        row
+                    )
+                    .expect("failed to print")
+                });
             }
         }
-        Ok(())
     }
 }
+
+fn workspace_name(entities: &Entities, workspace_id: i64) -> &str {
+    entities
+        .workspaces
+        .iter()
+        .find(|w| w.id == workspace_id)
+        .map(|w| w.name.as_str())
+        .unwrap_or("")
+}
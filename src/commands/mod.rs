@@ -1,4 +1,7 @@
 pub mod auth;
+pub mod backup;
+pub mod browse;
+pub mod bulk_edit;
 pub mod cont;
 pub mod create_project;
 pub mod create_tag;
@@ -6,9 +9,12 @@ pub mod delete;
 pub mod delete_project;
 pub mod delete_tag;
 pub mod edit;
+pub mod history;
 pub mod list;
 pub mod rename_project;
 pub mod rename_tag;
+pub mod restore;
 pub mod running;
 pub mod start;
 pub mod stop;
+pub mod undo;
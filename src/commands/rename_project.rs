@@ -1,39 +1,44 @@
 use crate::api::client::ApiClient;
+use crate::error::NotFoundError;
 use crate::models::ResultWithDefaultError;
-use colored::Colorize;
+use crate::output::{change_message, print_change_json, ToOutput};
 
 pub struct RenameProjectCommand;
 
 impl RenameProjectCommand {
     pub async fn execute(
         api_client: impl ApiClient,
+        workspace: Option<String>,
         old_name: String,
         new_name: String,
+        json: bool,
     ) -> ResultWithDefaultError<()> {
         let entities = api_client.get_entities().await?;
+        let entities = match workspace {
+            Some(workspace) => entities.in_workspace(entities.resolve_workspace(&workspace)?),
+            None => entities,
+        };
 
         let project = entities
             .projects
             .values()
             .find(|p| p.name == old_name)
-            .cloned();
+            .cloned()
+            .ok_or_else(|| -> Box<dyn std::error::Error + Send> {
+                Box::new(NotFoundError::Project(old_name.clone()))
+            })?;
 
-        match project {
-            None => println!(
-                "{}",
-                format!("No project found with name '{old_name}'").yellow()
-            ),
-            Some(project) => {
-                match api_client
-                    .rename_project(project.workspace_id, project.id, new_name)
-                    .await
-                {
-                    Err(error) => println!("{}\n{}", "Couldn't rename project".red(), error),
-                    Ok(project) => {
-                        println!("{}\n{}", "Project renamed successfully".green(), project)
-                    }
-                }
-            }
+        let project = api_client
+            .rename_project(project.workspace_id, project.id, new_name)
+            .await?;
+        if json {
+            print_change_json(&project.to_output());
+        } else {
+            println!(
+                "{}\n{}",
+                change_message("Project renamed successfully", "rename project"),
+                project
+            );
         }
 
         Ok(())
@@ -1,29 +1,39 @@
 use crate::api::client::ApiClient;
+use crate::error::NotFoundError;
 use crate::models::ResultWithDefaultError;
-use colored::Colorize;
+use crate::output::{change_message, print_change_json, ToOutput};
+use crate::workspace::resolve_workspace_id;
 
 pub struct RenameTagCommand;
 
 impl RenameTagCommand {
     pub async fn execute(
         api_client: impl ApiClient,
+        workspace: Option<String>,
         old_name: String,
         new_name: String,
+        json: bool,
     ) -> ResultWithDefaultError<()> {
-        let workspace_id = api_client.get_user().await?.default_workspace_id;
+        let workspace_id = resolve_workspace_id(&api_client, workspace.as_deref()).await?;
         let tags = api_client.get_tags(workspace_id).await?;
 
-        let tag = tags.into_iter().find(|t| t.name == old_name);
-
-        match tag {
-            None => println!(
-                "{}",
-                format!("No tag found with name '{old_name}'").yellow()
-            ),
-            Some(tag) => match api_client.rename_tag(workspace_id, tag.id, new_name).await {
-                Err(error) => println!("{}\n{}", "Couldn't rename tag".red(), error),
-                Ok(tag) => println!("{}\n{}", "Tag renamed successfully".green(), tag),
+        let tag = tags.into_iter().find(|t| t.name == old_name).ok_or_else(
+            || -> Box<dyn std::error::Error + Send> {
+                Box::new(NotFoundError::Tag(old_name.clone()))
             },
+        )?;
+
+        let tag = api_client
+            .rename_tag(workspace_id, tag.id, new_name)
+            .await?;
+        if json {
+            print_change_json(&tag.to_output());
+        } else {
+            println!(
+                "{}\n{}",
+                change_message("Tag renamed successfully", "rename tag"),
+                tag
+            );
         }
 
         Ok(())
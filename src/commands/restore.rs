@@ -0,0 +1,552 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::api::client::ApiClient;
+use crate::archive::{Archive, RestoreProgress};
+use crate::models::{Project, ResultWithDefaultError, Task, TimeEntry};
+use crate::output::print_json;
+use colored::Colorize;
+
+pub struct RestoreCommand;
+
+impl RestoreCommand {
+    pub async fn execute(
+        api_client: impl ApiClient,
+        path: PathBuf,
+        workspace: Option<String>,
+        dry_run: bool,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
+        let archive = Archive::read(&path)?;
+        let entities = api_client.get_entities().await?;
+        let workspace_id = match workspace {
+            Some(workspace) => entities.resolve_workspace(&workspace)?,
+            None => api_client.get_user().await?.default_workspace_id,
+        };
+
+        let progress_path = RestoreProgress::path_for(&path);
+        let mut progress = match RestoreProgress::load(&progress_path) {
+            Some(progress) if progress.workspace_id == workspace_id => {
+                if !json {
+                    println!(
+                        "{} {}",
+                        "Resuming restore from".yellow(),
+                        progress_path.display()
+                    );
+                }
+                progress
+            }
+            _ => RestoreProgress {
+                workspace_id,
+                ..Default::default()
+            },
+        };
+        let save = |progress: &RestoreProgress| -> ResultWithDefaultError<()> {
+            if dry_run {
+                Ok(())
+            } else {
+                progress.save(&progress_path)
+            }
+        };
+
+        // Everything is restored into one workspace, so clients, projects and
+        // tasks with the same name coming from different archived workspaces
+        // are merged into one, as tags are below.
+        let mut client_ids: HashMap<String, i64> = entities
+            .clients
+            .values()
+            .filter(|c| c.workspace_id == workspace_id)
+            .map(|c| (c.name.clone(), c.id))
+            .collect();
+        for client in archive.clients.iter() {
+            if let Some(new_id) = progress.clients.get(&client.id) {
+                client_ids.insert(client.name.clone(), *new_id);
+            }
+        }
+        for client in archive.clients.iter() {
+            if progress.clients.contains_key(&client.id) {
+                continue;
+            }
+            let new_id = match client_ids.get(&client.name) {
+                Some(existing_id) => *existing_id,
+                None if dry_run => {
+                    if !json {
+                        println!("Would create client {}", client.name.bold());
+                    }
+                    client.id
+                }
+                None => {
+                    api_client
+                        .create_client(workspace_id, client.name.clone())
+                        .await?
+                        .id
+                }
+            };
+            client_ids.insert(client.name.clone(), new_id);
+            progress.clients.insert(client.id, new_id);
+            save(&progress)?;
+        }
+
+        let mut project_ids: HashMap<String, i64> = entities
+            .projects
+            .values()
+            .filter(|p| p.workspace_id == workspace_id)
+            .map(|p| (p.name.clone(), p.id))
+            .collect();
+        for project in archive.projects.iter() {
+            if let Some(new_id) = progress.projects.get(&project.id) {
+                project_ids.insert(project.name.clone(), *new_id);
+            }
+        }
+        for project in archive.projects.iter() {
+            if progress.projects.contains_key(&project.id) {
+                continue;
+            }
+            let new_id = match project_ids.get(&project.name) {
+                Some(existing_id) => *existing_id,
+                None if dry_run => {
+                    if !json {
+                        println!("Would create project {project}");
+                    }
+                    project.id
+                }
+                None => {
+                    let client_id = project
+                        .client
+                        .as_ref()
+                        .and_then(|c| progress.clients.get(&c.id))
+                        .cloned();
+                    api_client
+                        .create_project(
+                            workspace_id,
+                            project.name.clone(),
+                            project.color.clone(),
+                            client_id,
+                        )
+                        .await?
+                        .id
+                }
+            };
+            project_ids.insert(project.name.clone(), new_id);
+            progress.projects.insert(project.id, new_id);
+            save(&progress)?;
+        }
+
+        let mut task_ids: HashMap<(i64, String), i64> = entities
+            .tasks
+            .values()
+            .filter(|t| t.workspace_id == workspace_id)
+            .map(|t| ((t.project.id, t.name.clone()), t.id))
+            .collect();
+        for task in archive.tasks.iter() {
+            if let (Some(new_id), Some(project)) = (
+                progress.tasks.get(&task.id),
+                remap_project(&progress, Some(&task.project)),
+            ) {
+                task_ids.insert((project.id, task.name.clone()), *new_id);
+            }
+        }
+        for task in archive.tasks.iter() {
+            if progress.tasks.contains_key(&task.id) {
+                continue;
+            }
+            let Some(project) = remap_project(&progress, Some(&task.project)) else {
+                continue;
+            };
+            let key = (project.id, task.name.clone());
+            let new_id = match task_ids.get(&key) {
+                Some(existing_id) => *existing_id,
+                None if dry_run => {
+                    if !json {
+                        println!(
+                            "Would create task {} in project {}",
+                            task.name.bold(),
+                            project
+                        );
+                    }
+                    task.id
+                }
+                None => {
+                    api_client
+                        .create_task(workspace_id, project, task.name.clone())
+                        .await?
+                        .id
+                }
+            };
+            task_ids.insert(key, new_id);
+            progress.tasks.insert(task.id, new_id);
+            save(&progress)?;
+        }
+
+        // Tag names are unique per workspace too
+        let mut tag_ids: HashMap<String, i64> = api_client
+            .get_tags(workspace_id)
+            .await?
+            .into_iter()
+            .map(|t| (t.name, t.id))
+            .collect();
+        for tag in archive.tags.iter() {
+            if let Some(new_id) = progress.tags.get(&tag.id) {
+                tag_ids.insert(tag.name.clone(), *new_id);
+            }
+        }
+        for tag in archive.tags.iter() {
+            if progress.tags.contains_key(&tag.id) {
+                continue;
+            }
+            let new_id = match tag_ids.get(&tag.name) {
+                Some(existing_id) => *existing_id,
+                None if dry_run => {
+                    if !json {
+                        println!("Would create tag {}", tag.name.bold());
+                    }
+                    tag.id
+                }
+                None => {
+                    api_client
+                        .create_tag(workspace_id, tag.name.clone())
+                        .await?
+                        .id
+                }
+            };
+            tag_ids.insert(tag.name.clone(), new_id);
+            progress.tags.insert(tag.id, new_id);
+            save(&progress)?;
+        }
+
+        for time_entry in archive.time_entries.iter() {
+            if progress.time_entries.contains_key(&time_entry.id) || time_entry.is_running() {
+                continue;
+            }
+            let task = time_entry.task.as_ref().and_then(|task| {
+                progress.tasks.get(&task.id).map(|id| Task {
+                    id: *id,
+                    workspace_id,
+                    ..task.clone()
+                })
+            });
+            let entry_to_create = TimeEntry {
+                workspace_id,
+                project: remap_project(&progress, time_entry.project.as_ref()),
+                task,
+                ..time_entry.clone()
+            };
+            if dry_run {
+                if !json {
+                    println!("Would create time entry {entry_to_create}");
+                }
+                progress.time_entries.insert(time_entry.id, time_entry.id);
+                continue;
+            }
+            let new_id = api_client.create_time_entry(entry_to_create).await?;
+            progress.time_entries.insert(time_entry.id, new_id);
+            save(&progress)?;
+        }
+
+        if !dry_run {
+            let _ = std::fs::remove_file(&progress_path);
+        }
+        if json {
+            print_json(&serde_json::json!({
+                "dry_run": dry_run,
+                "workspace_id": workspace_id,
+                "clients": progress.clients.len(),
+                "projects": progress.projects.len(),
+                "tasks": progress.tasks.len(),
+                "tags": progress.tags.len(),
+                "time_entries": progress.time_entries.len(),
+            }));
+            return Ok(());
+        }
+        if dry_run {
+            println!("{}", "Dry run complete, nothing was restored".yellow());
+            return Ok(());
+        }
+
+        println!(
+            "{}\n{} clients, {} projects, {} tasks, {} tags, {} time entries",
+            "Restore completed successfully".green(),
+            progress.clients.len(),
+            progress.projects.len(),
+            progress.tasks.len(),
+            progress.tags.len(),
+            progress.time_entries.len(),
+        );
+        Ok(())
+    }
+}
+
+fn remap_project(progress: &RestoreProgress, project: Option<&Project>) -> Option<Project> {
+    project.and_then(|project| {
+        progress.projects.get(&project.id).map(|id| Project {
+            id: *id,
+            workspace_id: progress.workspace_id,
+            ..project.clone()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::models::{Client, Entities, Tag, User};
+    use chrono::{Duration, Utc};
+    use mockall::Sequence;
+
+    const WORKSPACE_ID: i64 = 100;
+
+    fn project(id: i64, workspace_id: i64, client: Option<Client>) -> Project {
+        Project {
+            id,
+            name: "Website".to_string(),
+            workspace_id,
+            client,
+            is_private: false,
+            active: true,
+            at: Utc::now(),
+            created_at: Utc::now(),
+            color: "#06aaf5".to_string(),
+            billable: None,
+        }
+    }
+
+    fn time_entry(id: i64, project: &Project, task: &Task) -> TimeEntry {
+        let entry = TimeEntry {
+            id,
+            description: "Landing page".to_string(),
+            workspace_id: 1,
+            tags: vec!["design".to_string()],
+            project: Some(project.clone()),
+            task: Some(task.clone()),
+            ..TimeEntry::default()
+        };
+        entry.as_stopped_time_entry(entry.start + Duration::hours(1))
+    }
+
+    /// An archive of workspace 1 holding one of each entity: client 1,
+    /// project 2, task 3, tag 4 and time entries 5 and 6.
+    fn archive() -> Archive {
+        let client = Client {
+            id: 1,
+            name: "Acme".to_string(),
+            workspace_id: 1,
+        };
+        let project = project(2, 1, Some(client.clone()));
+        let task = Task {
+            id: 3,
+            name: "Design".to_string(),
+            workspace_id: 1,
+            project: project.clone(),
+        };
+        Archive {
+            version: crate::archive::ARCHIVE_VERSION,
+            created_at: Utc::now(),
+            workspaces: Vec::new(),
+            clients: vec![client],
+            projects: vec![project.clone()],
+            tasks: vec![task.clone()],
+            tags: vec![Tag {
+                id: 4,
+                name: "design".to_string(),
+                workspace_id: 1,
+            }],
+            time_entries: vec![
+                time_entry(5, &project, &task),
+                time_entry(6, &project, &task),
+            ],
+        }
+    }
+
+    fn api_client() -> MockApiClient {
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_get_entities()
+            .returning(|| Ok(Entities::default()));
+        api_client.expect_get_user().returning(|| {
+            Ok(User {
+                api_token: String::new(),
+                email: String::new(),
+                fullname: None,
+                timezone: "UTC".to_string(),
+                default_workspace_id: WORKSPACE_ID,
+                created_at: None,
+            })
+        });
+        api_client.expect_get_tags().returning(|_| Ok(Vec::new()));
+        api_client
+    }
+
+    fn remapped(te: &TimeEntry, id: i64) -> bool {
+        te.id == id
+            && te.workspace_id == WORKSPACE_ID
+            && te.project.as_ref().is_some_and(|p| p.id == 12)
+            && te.task.as_ref().is_some_and(|t| t.id == 13)
+            && te.tags == ["design"]
+    }
+
+    #[tokio::test]
+    async fn entities_are_recreated_in_order_and_entries_point_at_the_new_ids() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("backup.json");
+        archive().write(&path).unwrap();
+
+        let mut api_client = api_client();
+        let mut sequence = Sequence::new();
+        api_client
+            .expect_create_client()
+            .withf(|workspace_id, name| *workspace_id == WORKSPACE_ID && name == "Acme")
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|workspace_id, name| {
+                Ok(Client {
+                    id: 11,
+                    name,
+                    workspace_id,
+                })
+            });
+        api_client
+            .expect_create_project()
+            .withf(|_, name, _, client_id| name == "Website" && *client_id == Some(11))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|workspace_id, _, _, _| Ok(project(12, workspace_id, None)));
+        api_client
+            .expect_create_task()
+            .withf(|_, project, name| project.id == 12 && name == "Design")
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|workspace_id, project, name| {
+                Ok(Task {
+                    id: 13,
+                    name,
+                    workspace_id,
+                    project,
+                })
+            });
+        api_client
+            .expect_create_tag()
+            .withf(|_, name| name == "design")
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|workspace_id, name| {
+                Ok(Tag {
+                    id: 14,
+                    name,
+                    workspace_id,
+                })
+            });
+        api_client
+            .expect_create_time_entry()
+            .withf(|te| remapped(te, 5))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_| Ok(15));
+        api_client
+            .expect_create_time_entry()
+            .withf(|te| remapped(te, 6))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_| Ok(16));
+
+        RestoreCommand::execute(api_client, path.clone(), None, false, true)
+            .await
+            .unwrap();
+        assert!(!RestoreProgress::path_for(&path).exists());
+    }
+
+    #[tokio::test]
+    async fn an_interrupted_restore_resumes_from_its_progress_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("backup.json");
+        archive().write(&path).unwrap();
+        RestoreProgress {
+            workspace_id: WORKSPACE_ID,
+            clients: [(1, 11)].into_iter().collect(),
+            projects: [(2, 12)].into_iter().collect(),
+            tasks: [(3, 13)].into_iter().collect(),
+            tags: [(4, 14)].into_iter().collect(),
+            time_entries: [(5, 15)].into_iter().collect(),
+        }
+        .save(&RestoreProgress::path_for(&path))
+        .unwrap();
+
+        // Nothing restored before is created again
+        let mut api_client = api_client();
+        api_client
+            .expect_create_time_entry()
+            .withf(|te| remapped(te, 6))
+            .times(1)
+            .returning(|_| Ok(16));
+
+        RestoreCommand::execute(api_client, path.clone(), None, false, true)
+            .await
+            .unwrap();
+        assert!(!RestoreProgress::path_for(&path).exists());
+    }
+
+    #[tokio::test]
+    async fn projects_of_the_same_name_from_two_workspaces_are_merged() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("backup.json");
+        let mut archive = archive();
+        let other_website = project(7, 2, None);
+        let other_entry = TimeEntry {
+            id: 8,
+            workspace_id: 2,
+            project: Some(other_website.clone()),
+            ..TimeEntry::default()
+        };
+        archive.projects.push(other_website);
+        archive
+            .time_entries
+            .push(other_entry.as_stopped_time_entry(other_entry.start + Duration::hours(1)));
+        archive.write(&path).unwrap();
+
+        let mut api_client = api_client();
+        api_client
+            .expect_create_client()
+            .times(1)
+            .returning(|workspace_id, name| {
+                Ok(Client {
+                    id: 11,
+                    name,
+                    workspace_id,
+                })
+            });
+        api_client
+            .expect_create_project()
+            .times(1)
+            .returning(|workspace_id, _, _, _| Ok(project(12, workspace_id, None)));
+        api_client
+            .expect_create_task()
+            .times(1)
+            .returning(|workspace_id, project, name| {
+                Ok(Task {
+                    id: 13,
+                    name,
+                    workspace_id,
+                    project,
+                })
+            });
+        api_client
+            .expect_create_tag()
+            .times(1)
+            .returning(|workspace_id, name| {
+                Ok(Tag {
+                    id: 14,
+                    name,
+                    workspace_id,
+                })
+            });
+        api_client
+            .expect_create_time_entry()
+            .withf(|te| te.project.as_ref().is_some_and(|p| p.id == 12))
+            .times(3)
+            .returning(|te| Ok(te.id + 10));
+
+        RestoreCommand::execute(api_client, path, None, false, true)
+            .await
+            .unwrap();
+    }
+}
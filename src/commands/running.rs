@@ -1,5 +1,6 @@
 use crate::api;
 use crate::models;
+use crate::output::{print_json, ToOutput};
 use api::client::ApiClient;
 use colored::Colorize;
 use models::ResultWithDefaultError;
@@ -7,8 +8,12 @@ use models::ResultWithDefaultError;
 pub struct RunningTimeEntryCommand;
 
 impl RunningTimeEntryCommand {
-    pub async fn execute(api_client: impl ApiClient) -> ResultWithDefaultError<()> {
+    pub async fn execute(api_client: impl ApiClient, json: bool) -> ResultWithDefaultError<()> {
         let entities = api_client.get_entities().await?;
+        if json {
+            print_json(&entities.running_time_entry().map(|te| te.to_output()));
+            return Ok(());
+        }
         match entities.running_time_entry() {
             None => println!("{}", "No time entry is running at the moment".yellow()),
             Some(running_time_entry) => println!("{running_time_entry}"),
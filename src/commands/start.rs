@@ -1,22 +1,32 @@
 use crate::api;
 use crate::commands;
 use crate::config;
+use crate::constants;
+use crate::error::{ArgumentError, NotFoundError, PickerError};
+use crate::fuzzy;
 use crate::models;
 use crate::models::Entities;
+use crate::output::{change_message, print_change_json, ToOutput};
+use crate::picker;
+use crate::picker::preview;
 use crate::picker::ItemPicker;
 use crate::picker::PickableItem;
 use crate::picker::PickableItemKind;
 use crate::utilities;
 use api::client::ApiClient;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
 use commands::stop::{StopCommand, StopCommandOrigin};
-use models::{ResultWithDefaultError, TimeEntry};
+use models::{Project, ResultWithDefaultError, Tag, Task, TimeEntry};
+use std::collections::HashMap;
+use std::io::IsTerminal;
 
 pub struct StartCommand;
 
 fn interactively_create_time_entry(
     time_entry: TimeEntry,
     entities: Entities,
+    known_tags: Vec<Tag>,
     picker: Box<dyn ItemPicker>,
 ) -> TimeEntry {
     let yes_or_default_no = [
@@ -26,6 +36,12 @@ fn interactively_create_time_entry(
         "".to_string(),
     ];
 
+    let time_entry = if time_entry.description.is_empty() {
+        pick_description(time_entry, &entities, picker.as_ref())
+    } else {
+        time_entry
+    };
+
     let (project, task) = match time_entry.project {
         Some(_) => (time_entry.project, None),
         None => {
@@ -34,22 +50,15 @@ fn interactively_create_time_entry(
             } else {
                 let mut pickable_items: Vec<PickableItem> = entities
                     .projects
-                    .clone()
-                    .into_values()
-                    .map(PickableItem::from_project)
+                    .values()
+                    .map(|p| project_item(p, &entities))
                     .collect();
 
-                pickable_items.extend(
-                    entities
-                        .tasks
-                        .clone()
-                        .into_values()
-                        .map(PickableItem::from_task),
-                );
+                pickable_items.extend(entities.tasks.values().map(|t| task_item(t, &entities)));
 
                 match picker.pick(pickable_items) {
                     Ok(picked_key) => match picked_key.kind {
-                        PickableItemKind::TimeEntry => (None, None),
+                        PickableItemKind::TimeEntry | PickableItemKind::Tag => (None, None),
                         PickableItemKind::Project => {
                             (entities.projects.get(&picked_key.id).cloned(), None)
                         }
@@ -65,6 +74,14 @@ fn interactively_create_time_entry(
         }
     };
 
+    // Cancelling the tag step keeps the tags from the flags or directory config
+    let tags = if known_tags.is_empty() {
+        time_entry.tags.clone()
+    } else {
+        picker::pick_tags(picker.as_ref(), &known_tags, &time_entry.tags)
+            .unwrap_or(time_entry.tags.clone())
+    };
+
     // Only ask for billable if the user didn't provide a value AND if the selected project doesn't have a default billable setting.
     let billable = time_entry.billable
         || project.clone().and_then(|p| p.billable).unwrap_or(
@@ -80,85 +97,368 @@ fn interactively_create_time_entry(
         billable,
         project,
         task,
+        tags,
         ..time_entry
     }
 }
 
+/// Suggests the descriptions of earlier entries in the workspace. Picking one
+/// also brings along its project, task, tags and billable flag where the
+/// flags and directory config left them unset; typing something else only
+/// sets the description.
+fn pick_description(
+    time_entry: TimeEntry,
+    entities: &Entities,
+    picker: &dyn ItemPicker,
+) -> TimeEntry {
+    let suggestions = description_suggestions(entities, time_entry.workspace_id, Utc::now());
+    if suggestions.is_empty() {
+        return time_entry;
+    }
+    let items = suggestions
+        .iter()
+        .map(|te| {
+            PickableItem::from_description(te.clone())
+                .with_preview(preview::time_entry(te, &entities.time_entries))
+        })
+        .collect();
+    let selection = match picker.pick_or_type(items) {
+        Ok(selection) => selection,
+        Err(_) => return time_entry,
+    };
+
+    match selection.keys.first() {
+        Some(key) => {
+            let previous = suggestions.into_iter().find(|te| te.id == key.id).unwrap();
+            let (project, task) = match time_entry.project {
+                Some(_) => (time_entry.project.clone(), time_entry.task.clone()),
+                None => (previous.project, previous.task),
+            };
+            TimeEntry {
+                description: previous.description,
+                project,
+                task,
+                tags: if time_entry.tags.is_empty() {
+                    previous.tags
+                } else {
+                    time_entry.tags.clone()
+                },
+                billable: time_entry.billable || previous.billable,
+                ..time_entry
+            }
+        }
+        None => TimeEntry {
+            description: selection.query.unwrap_or_default(),
+            ..time_entry
+        },
+    }
+}
+
+/// The newest entry for each description used in the workspace, ranked by how
+/// often and how recently the description was used. Each use counts half as
+/// much for every week that has passed since.
+fn description_suggestions(
+    entities: &Entities,
+    workspace_id: i64,
+    now: DateTime<Utc>,
+) -> Vec<TimeEntry> {
+    let mut ranked: HashMap<&str, (f64, &TimeEntry)> = HashMap::new();
+    for time_entry in entities
+        .time_entries
+        .iter()
+        .filter(|te| te.workspace_id == workspace_id && !te.description.is_empty())
+    {
+        let age_in_weeks = (now - time_entry.start).num_hours() as f64 / (24.0 * 7.0);
+        let weight = 0.5_f64.powf(age_in_weeks.max(0.0));
+        let (score, newest) = ranked
+            .entry(time_entry.description.as_str())
+            .or_insert((0.0, time_entry));
+        *score += weight;
+        if time_entry.start > newest.start {
+            *newest = time_entry;
+        }
+    }
+
+    let mut ranked: Vec<(f64, &TimeEntry)> = ranked.into_values().collect();
+    ranked.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .total_cmp(a_score)
+            .then_with(|| b.start.cmp(&a.start))
+    });
+    ranked.into_iter().map(|(_, te)| te.clone()).collect()
+}
+
+/// Uses the project that best matches `name`, letting the user pick when
+/// several match equally well. A missing project is created when asked to.
+async fn resolve_project(
+    api_client: &impl ApiClient,
+    entities: &Entities,
+    picker: &dyn ItemPicker,
+    workspace_id: i64,
+    name: &str,
+    create: bool,
+) -> ResultWithDefaultError<Project> {
+    let candidates = entities
+        .projects
+        .values()
+        .filter(|p| p.workspace_id == workspace_id);
+    let matches = fuzzy::best_matches(candidates, name, |p| p.name.as_str());
+    match matches.as_slice() {
+        [] if create => {
+            let project = api_client
+                .create_project(
+                    workspace_id,
+                    name.to_string(),
+                    constants::DEFAULT_PROJECT_COLOR.to_string(),
+                    None,
+                )
+                .await?;
+            eprintln!(
+                "{} {}",
+                change_message("Created project", "create project"),
+                project.name
+            );
+            Ok(project)
+        }
+        [] => Err(Box::new(NotFoundError::Project(name.to_string()))),
+        [project] if fuzzy::is_close_match(name, &project.name) => Ok((*project).clone()),
+        [project] => {
+            let key = pick_one(
+                picker,
+                vec![project_item(project, entities)],
+                ArgumentError::LooseMatch(
+                    "project".to_string(),
+                    name.to_string(),
+                    project.name.clone(),
+                ),
+            )?;
+            Ok(entities.projects.get(&key).cloned().unwrap())
+        }
+        projects => {
+            let names = projects.iter().map(|p| p.name.clone()).collect();
+            let key = pick_one(
+                picker,
+                projects.iter().map(|p| project_item(p, entities)).collect(),
+                ArgumentError::AmbiguousName("project".to_string(), name.to_string(), names),
+            )?;
+            Ok(entities.projects.get(&key).cloned().unwrap())
+        }
+    }
+}
+
+/// Like `resolve_project`, looking only at the tasks of the project when one
+/// was given. New tasks are created in that project.
+async fn resolve_task(
+    api_client: &impl ApiClient,
+    entities: &Entities,
+    picker: &dyn ItemPicker,
+    workspace_id: i64,
+    project: Option<&Project>,
+    name: &str,
+    create: bool,
+) -> ResultWithDefaultError<Task> {
+    let candidates = entities
+        .tasks
+        .values()
+        .filter(|t| t.workspace_id == workspace_id && project.is_none_or(|p| t.project.id == p.id));
+    let matches = fuzzy::best_matches(candidates, name, |t| t.name.as_str());
+    match matches.as_slice() {
+        [] if create => {
+            let project = project.ok_or_else(|| -> Box<dyn std::error::Error + Send> {
+                Box::new(ArgumentError::TaskNeedsProject)
+            })?;
+            let task = api_client
+                .create_task(project.workspace_id, project.clone(), name.to_string())
+                .await?;
+            eprintln!(
+                "{} {}",
+                change_message("Created task", "create task"),
+                task.name
+            );
+            Ok(task)
+        }
+        [] => Err(Box::new(NotFoundError::Task(name.to_string()))),
+        [task] if fuzzy::is_close_match(name, &task.name) => Ok((*task).clone()),
+        [task] => {
+            let key = pick_one(
+                picker,
+                vec![task_item(task, entities)],
+                ArgumentError::LooseMatch("task".to_string(), name.to_string(), task.name.clone()),
+            )?;
+            Ok(entities.tasks.get(&key).cloned().unwrap())
+        }
+        tasks => {
+            let names = tasks
+                .iter()
+                .map(|t| format!("{} ({})", t.name, t.project.name))
+                .collect();
+            let key = pick_one(
+                picker,
+                tasks.iter().map(|t| task_item(t, entities)).collect(),
+                ArgumentError::AmbiguousName("task".to_string(), name.to_string(), names),
+            )?;
+            Ok(entities.tasks.get(&key).cloned().unwrap())
+        }
+    }
+}
+
+fn project_item(project: &Project, entities: &Entities) -> PickableItem {
+    PickableItem::from_project(project.clone())
+        .with_preview(preview::project(project, &entities.time_entries))
+}
+
+/// Tasks show the preview of their project.
+fn task_item(task: &Task, entities: &Entities) -> PickableItem {
+    let project = entities
+        .projects
+        .get(&task.project.id)
+        .unwrap_or(&task.project);
+    PickableItem::from_task(task.clone())
+        .with_preview(preview::project(project, &entities.time_entries))
+}
+
+/// Picks among equally good matches, or confirms a loose one, failing with
+/// `ambiguous` when there is no terminal to show the picker on.
+fn pick_one(
+    picker: &dyn ItemPicker,
+    items: Vec<PickableItem>,
+    ambiguous: ArgumentError,
+) -> ResultWithDefaultError<i64> {
+    if !std::io::stdin().is_terminal() {
+        return Err(Box::new(ambiguous));
+    }
+    Ok(picker.pick(items)?.id)
+}
+
+/// Spells known tags the way the workspace does. Tags the workspace doesn't
+/// have yet would be created by the API as a side effect, so they need
+/// confirmation (or `--create-tags`) and come with a hint for likely typos.
+fn resolve_tags(
+    tags: Vec<String>,
+    known_tags: &[Tag],
+    create: bool,
+) -> ResultWithDefaultError<Vec<String>> {
+    let mut resolved = Vec::new();
+    let mut unknown = Vec::new();
+    for tag in tags {
+        match known_tags
+            .iter()
+            .find(|known| known.name.to_lowercase() == tag.to_lowercase())
+        {
+            Some(known) => resolved.push(known.name.clone()),
+            None => {
+                unknown.push(tag.clone());
+                resolved.push(tag);
+            }
+        }
+    }
+    if unknown.is_empty() || create {
+        return Ok(resolved);
+    }
+
+    let summary = unknown
+        .iter()
+        .map(|tag| {
+            let suggestions = fuzzy::best_matches(known_tags, tag, |t| t.name.as_str())
+                .iter()
+                .map(|t| t.name.clone())
+                .collect::<Vec<String>>();
+            if suggestions.is_empty() {
+                format!("{} {tag}", "New tag:".yellow())
+            } else {
+                format!(
+                    "{} {tag} (did you mean {}?)",
+                    "New tag:".yellow(),
+                    suggestions.join(", ")
+                )
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    if utilities::confirm(
+        &summary,
+        &format!("Create {} new tags?", unknown.len()),
+        false,
+    )? {
+        Ok(resolved)
+    } else {
+        Err(Box::new(PickerError::Cancelled))
+    }
+}
+
 impl StartCommand {
     #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         api_client: impl ApiClient,
         picker: Box<dyn ItemPicker>,
+        workspace: Option<String>,
+        default_workspace: Option<String>,
         description: Option<String>,
         project_name: Option<String>,
         tags: Option<Vec<String>>,
         billable: bool,
         interactive: bool,
         task: Option<String>,
+        create_project: bool,
+        create_task: bool,
+        create_tags: bool,
+        yes: bool,
+        json: bool,
     ) -> ResultWithDefaultError<()> {
-        StopCommand::execute(&api_client, StopCommandOrigin::StartCommand).await?;
-
-        let workspace_id = (api_client.get_user().await?).default_workspace_id;
         let entities = api_client.get_entities().await?;
+        let workspace_from_flag = match workspace {
+            Some(workspace) => Some(entities.resolve_workspace(&workspace)?),
+            None => None,
+        };
+        let entities = match workspace_from_flag {
+            Some(workspace_id) => entities.in_workspace(workspace_id),
+            None => entities,
+        };
 
         let default_time_entry = config::locate::locate_config_path()
             .and_then(config::parser::get_config_from_file)
             .and_then(|track_config| track_config.get_default_entry(entities.clone()))
             .unwrap_or_else(|_| TimeEntry::default());
 
-        let workspace_id = if default_time_entry.workspace_id != -1 {
-            default_time_entry.workspace_id
-        } else {
-            workspace_id
+        let workspace_id = match workspace_from_flag {
+            Some(workspace_id) => workspace_id,
+            None if default_time_entry.workspace_id != -1 => default_time_entry.workspace_id,
+            None => match default_workspace {
+                Some(workspace) => entities.resolve_workspace(&workspace)?,
+                None => api_client.get_user().await?.default_workspace_id,
+            },
         };
 
-        // Look up project by name if provided
-        let project_from_flag = project_name.as_ref().and_then(|name| {
-            entities
-                .projects
-                .clone()
-                .into_values()
-                .find(|p| p.name == *name)
-        });
-
-        // Error if project name was provided but not found
-        if let Some(name) = &project_name {
-            if project_from_flag.is_none() {
-                return Err(Box::new(std::io::Error::other(format!(
-                    "Project \"{}\" not found",
-                    name
-                ))));
-            }
-        }
-
-        // Look up task by name if provided
-        let task_obj = task.as_ref().and_then(|task_name| {
-            entities
-                .tasks
-                .clone()
-                .into_values()
-                .find(|t| t.name == *task_name)
-        });
-
-        // Error if task name was provided but not found
-        if let Some(name) = &task {
-            if task_obj.is_none() {
-                return Err(Box::new(std::io::Error::other(format!(
-                    "Task \"{}\" not found",
-                    name
-                ))));
-            }
-        }
+        let project_from_flag = match project_name {
+            Some(name) => Some(
+                resolve_project(
+                    &api_client,
+                    &entities,
+                    picker.as_ref(),
+                    workspace_id,
+                    &name,
+                    create_project,
+                )
+                .await?,
+            ),
+            None => None,
+        };
 
-        // Validate: if both project and task are specified, ensure task belongs to project
-        if let (Some(ref proj), Some(ref tsk)) = (&project_from_flag, &task_obj) {
-            if tsk.project.id != proj.id {
-                return Err(Box::new(std::io::Error::other(format!(
-                    "Task \"{}\" belongs to project \"{}\", but you specified project \"{}\"",
-                    tsk.name, tsk.project.name, proj.name
-                ))));
-            }
-        }
+        let task_obj = match task {
+            Some(name) => Some(
+                resolve_task(
+                    &api_client,
+                    &entities,
+                    picker.as_ref(),
+                    workspace_id,
+                    project_from_flag.as_ref(),
+                    &name,
+                    create_task,
+                )
+                .await?,
+            ),
+            None => None,
+        };
 
         // Determine final task: use CLI flag if provided, otherwise fallback to config
         let task_obj = task_obj.or(default_time_entry.task.clone());
@@ -173,14 +473,23 @@ impl StartCommand {
 
         if let (Some(ref proj), Some(ref tsk)) = (&project, &task_obj) {
             if tsk.project.id != proj.id {
-                return Err(Box::new(std::io::Error::other(format!(
-                    "Task \"{}\" belongs to project \"{}\", but project \"{}\" was selected",
-                    tsk.name, tsk.project.name, proj.name
-                ))));
+                return Err(Box::new(ArgumentError::TaskNotInProject(
+                    tsk.name.clone(),
+                    tsk.project.name.clone(),
+                    proj.name.clone(),
+                )));
             }
         }
 
-        let tags = tags.unwrap_or(default_time_entry.tags.clone());
+        let known_tags = if tags.is_some() || interactive {
+            api_client.get_tags(workspace_id).await?
+        } else {
+            Vec::new()
+        };
+        let tags = match tags {
+            Some(tags) => resolve_tags(tags, &known_tags, create_tags || yes)?,
+            None => default_time_entry.tags.clone(),
+        };
 
         let billable = billable
             || default_time_entry.billable
@@ -199,22 +508,73 @@ impl StartCommand {
                 ..TimeEntry::default()
             };
             if interactive {
-                interactively_create_time_entry(initial_entry, entities.clone(), picker)
+                interactively_create_time_entry(initial_entry, entities.clone(), known_tags, picker)
             } else {
                 initial_entry
             }
         };
 
-        let started_entry_id = api_client
+        // Stop only once every name has been resolved, so a typo leaves the
+        // running entry alone.
+        StopCommand::execute(&api_client, StopCommandOrigin::StartCommand, json).await?;
+        let id = api_client
             .create_time_entry(time_entry_to_create.clone())
-            .await;
-        if started_entry_id.is_err() {
-            println!("{}", "Failed to start time entry".red());
-            return Err(started_entry_id.err().unwrap());
-        }
+            .await?;
+        let started_entry = TimeEntry {
+            id,
+            ..time_entry_to_create
+        };
 
-        println!("{}\n{}", "Time entry started".green(), time_entry_to_create);
+        if json {
+            print_change_json(&started_entry.to_output());
+        } else {
+            println!(
+                "{}\n{}",
+                change_message("Time entry started", "start time entry"),
+                started_entry
+            );
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn entry(id: i64, description: &str, days_ago: i64, now: DateTime<Utc>) -> TimeEntry {
+        TimeEntry {
+            id,
+            description: description.to_string(),
+            start: now - Duration::days(days_ago),
+            workspace_id: 1,
+            ..TimeEntry::default()
+        }
+    }
+
+    #[test]
+    fn recent_descriptions_outrank_old_frequent_ones() {
+        let now = Utc::now();
+        let entities = Entities {
+            time_entries: vec![
+                entry(1, "Planning", 30, now),
+                entry(2, "Planning", 31, now),
+                entry(3, "Planning", 32, now),
+                entry(4, "Standup", 1, now),
+                entry(5, "Review", 2, now),
+                entry(6, "Review", 3, now),
+                entry(7, "", 0, now),
+            ],
+            ..Entities::default()
+        };
+
+        let suggestions = description_suggestions(&entities, 1, now);
+
+        assert_eq!(
+            suggestions.iter().map(|te| te.id).collect::<Vec<i64>>(),
+            [5, 4, 1]
+        );
+    }
+}
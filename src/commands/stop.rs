@@ -1,5 +1,6 @@
 use crate::api;
 use crate::models;
+use crate::output::{change_message, print_change_json, print_json, ToOutput};
 use api::client::ApiClient;
 use chrono::Utc;
 use colored::Colorize;
@@ -17,11 +18,13 @@ impl StopCommand {
     pub async fn execute(
         api_client: &impl ApiClient,
         origin: StopCommandOrigin,
+        json: bool,
     ) -> ResultWithDefaultError<Option<TimeEntry>> {
         let entities = api_client.get_entities().await?;
         match entities.running_time_entry() {
             None => {
                 match origin {
+                    StopCommandOrigin::CommandLine if json => print_json(&None::<()>),
                     StopCommandOrigin::CommandLine => {
                         println!("{}", "No time entry is running at the moment".yellow())
                     }
@@ -38,10 +41,21 @@ impl StopCommand {
                     .update_time_entry(stopped_time_entry.clone())
                     .await?;
 
+                if json {
+                    if let StopCommandOrigin::CommandLine = origin {
+                        print_change_json(&stopped_time_entry.to_output());
+                    }
+                    return Ok(Some(stopped_time_entry));
+                }
+
                 let message = match origin {
-                    StopCommandOrigin::CommandLine => "Time entry stopped successfully".green(),
-                    StopCommandOrigin::StartCommand => "Running time entry stopped".yellow(),
-                    StopCommandOrigin::ContinueCommand => "Running time entry stopped".yellow(),
+                    StopCommandOrigin::CommandLine => {
+                        change_message("Time entry stopped successfully", "stop time entry")
+                    }
+                    StopCommandOrigin::StartCommand | StopCommandOrigin::ContinueCommand => {
+                        change_message("Running time entry stopped", "stop running time entry")
+                            .yellow()
+                    }
                 };
 
                 println!("{}\n{}", message, stopped_time_entry.clone());
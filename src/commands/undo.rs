@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+use crate::api::client::ApiClient;
+use crate::error::JournalError;
+use crate::journal::{Journal, JournalEntry, Operation};
+use crate::models::{ResultWithDefaultError, Task, TimeEntry};
+use crate::output::{change_message, print_change_json, print_json, ToOutput};
+use crate::utilities;
+use colored::Colorize;
+
+pub struct UndoCommand;
+
+impl UndoCommand {
+    /// Reverts the last `count` operations that haven't been undone yet,
+    /// newest first. Undoing a deletion recreates the entity, so it comes
+    /// back with a new id.
+    pub async fn execute(
+        api_client: impl ApiClient,
+        journal: Journal,
+        count: usize,
+        dry_run: bool,
+        yes: bool,
+        json: bool,
+    ) -> ResultWithDefaultError<()> {
+        let entries: Vec<JournalEntry> = journal
+            .read()?
+            .into_iter()
+            .rev()
+            .filter(|entry| !entry.undone)
+            .take(count)
+            .collect();
+        if entries.is_empty() {
+            return Err(Box::new(JournalError::NothingToUndo));
+        }
+
+        let summary = entries
+            .iter()
+            .map(|entry| format!("{} {}", "↶".yellow().bold(), entry.operation))
+            .collect::<Vec<String>>()
+            .join("\n");
+        if !utilities::confirm(
+            &summary,
+            &format!("Undo {} operations?", entries.len()),
+            yes,
+        )? {
+            if json {
+                print_json(&Vec::<()>::new());
+            } else {
+                println!("{}", "Nothing undone".yellow());
+            }
+            return Ok(());
+        }
+
+        let mut recreated = RecreatedIds::default();
+        let mut undone = Vec::new();
+        for entry in entries {
+            revert(&api_client, &entry.operation, &mut recreated).await?;
+            if !dry_run {
+                journal.mark_undone(entry.id)?;
+            }
+            if !json {
+                println!("{} {}", change_message("Undone:", "undo:"), entry.operation);
+            }
+            undone.push(entry);
+        }
+
+        if json {
+            print_change_json(&undone.iter().map(ToOutput::to_output).collect::<Vec<_>>());
+        }
+        Ok(())
+    }
+}
+
+/// Ids of entities recreated while undoing deletions, keyed by their old id,
+/// so that older operations on the same entity still find it.
+#[derive(Default)]
+struct RecreatedIds {
+    time_entries: HashMap<i64, i64>,
+    projects: HashMap<i64, i64>,
+    tasks: HashMap<i64, i64>,
+    tags: HashMap<i64, i64>,
+}
+
+fn current(ids: &HashMap<i64, i64>, id: i64) -> i64 {
+    *ids.get(&id).unwrap_or(&id)
+}
+
+fn missing_state(operation: &Operation) -> Box<dyn std::error::Error + Send> {
+    Box::new(JournalError::MissingState(operation.to_string()))
+}
+
+async fn revert(
+    api_client: &impl ApiClient,
+    operation: &Operation,
+    recreated: &mut RecreatedIds,
+) -> ResultWithDefaultError<()> {
+    match operation {
+        Operation::CreateTimeEntry { after } => {
+            api_client
+                .delete_time_entry(
+                    after.workspace_id,
+                    current(&recreated.time_entries, after.id),
+                )
+                .await
+        }
+        Operation::UpdateTimeEntry { before, .. } => {
+            let before = before.clone().ok_or_else(|| missing_state(operation))?;
+            let before = with_recreated_ids(api_client, before, recreated).await?;
+            api_client
+                .update_time_entry(TimeEntry {
+                    id: current(&recreated.time_entries, before.id),
+                    ..before
+                })
+                .await
+                .map(|_| ())
+        }
+        Operation::DeleteTimeEntry { id, before, .. } => {
+            let before = before.clone().ok_or_else(|| missing_state(operation))?;
+            let before = with_recreated_ids(api_client, before, recreated).await?;
+            let new_id = api_client.create_time_entry(before).await?;
+            recreated.time_entries.insert(*id, new_id);
+            Ok(())
+        }
+        Operation::CreateProject { after } => {
+            api_client
+                .delete_project(after.workspace_id, current(&recreated.projects, after.id))
+                .await
+        }
+        Operation::RenameProject { before, after } => {
+            let before = before.as_ref().ok_or_else(|| missing_state(operation))?;
+            api_client
+                .rename_project(
+                    after.workspace_id,
+                    current(&recreated.projects, after.id),
+                    before.name.clone(),
+                )
+                .await
+                .map(|_| ())
+        }
+        Operation::DeleteProject {
+            workspace_id,
+            id,
+            before,
+        } => {
+            let before = before.as_ref().ok_or_else(|| missing_state(operation))?;
+            let project = api_client
+                .create_project(
+                    *workspace_id,
+                    before.name.clone(),
+                    before.color.clone(),
+                    before.client.as_ref().map(|c| c.id),
+                )
+                .await?;
+            recreated.projects.insert(*id, project.id);
+            Ok(())
+        }
+        Operation::CreateTag { after } => {
+            api_client
+                .delete_tag(after.workspace_id, current(&recreated.tags, after.id))
+                .await
+        }
+        Operation::RenameTag { before, after } => {
+            let before = before.as_ref().ok_or_else(|| missing_state(operation))?;
+            api_client
+                .rename_tag(
+                    after.workspace_id,
+                    current(&recreated.tags, after.id),
+                    before.name.clone(),
+                )
+                .await
+                .map(|_| ())
+        }
+        Operation::DeleteTag {
+            workspace_id,
+            id,
+            before,
+        } => {
+            let before = before.as_ref().ok_or_else(|| missing_state(operation))?;
+            let tag = api_client
+                .create_tag(*workspace_id, before.name.clone())
+                .await?;
+            recreated.tags.insert(*id, tag.id);
+            Ok(())
+        }
+        Operation::CreateClient { after } => {
+            api_client.delete_client(after.workspace_id, after.id).await
+        }
+        Operation::CreateTask { after } => {
+            api_client
+                .delete_task(
+                    after.workspace_id,
+                    current(&recreated.projects, after.project.id),
+                    current(&recreated.tasks, after.id),
+                )
+                .await
+        }
+    }
+}
+
+/// `time_entry` pointing at the recreated project and task. A recreated
+/// project comes back without its tasks, so the entry's task is recreated in
+/// it too, once for all the entries that need it.
+async fn with_recreated_ids(
+    api_client: &impl ApiClient,
+    time_entry: TimeEntry,
+    recreated: &mut RecreatedIds,
+) -> ResultWithDefaultError<TimeEntry> {
+    let project = time_entry
+        .project
+        .clone()
+        .map(|project| crate::models::Project {
+            id: current(&recreated.projects, project.id),
+            ..project
+        });
+    let task = match (time_entry.task.clone(), &project) {
+        (Some(task), Some(project)) if recreated.tasks.contains_key(&task.id) => Some(Task {
+            id: current(&recreated.tasks, task.id),
+            project: project.clone(),
+            ..task
+        }),
+        (Some(task), Some(project)) if recreated.projects.contains_key(&task.project.id) => {
+            let new_task = api_client
+                .create_task(task.workspace_id, project.clone(), task.name.clone())
+                .await?;
+            recreated.tasks.insert(task.id, new_task.id);
+            Some(new_task)
+        }
+        (task, _) => task,
+    };
+    Ok(TimeEntry {
+        project,
+        task,
+        ..time_entry
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::MockApiClient;
+    use crate::models::Project;
+    use chrono::Utc;
+
+    fn project(id: i64) -> Project {
+        Project {
+            id,
+            name: "Website".to_string(),
+            workspace_id: 1,
+            client: None,
+            is_private: false,
+            active: true,
+            at: Utc::now(),
+            created_at: Utc::now(),
+            color: "#06aaf5".to_string(),
+            billable: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn a_recreated_project_gets_back_the_task_of_a_recreated_entry() {
+        let directory = tempfile::tempdir().unwrap();
+        let journal = Journal::new(directory.path().join("default.jsonl"), 30);
+        let entry = TimeEntry {
+            id: 4,
+            workspace_id: 1,
+            project: Some(project(2)),
+            task: Some(Task {
+                id: 3,
+                name: "Design".to_string(),
+                workspace_id: 1,
+                project: project(2),
+            }),
+            ..TimeEntry::default()
+        };
+        journal
+            .record(Operation::DeleteTimeEntry {
+                workspace_id: 1,
+                id: 4,
+                before: Some(entry),
+            })
+            .unwrap();
+        journal
+            .record(Operation::DeleteProject {
+                workspace_id: 1,
+                id: 2,
+                before: Some(project(2)),
+            })
+            .unwrap();
+
+        let mut api_client = MockApiClient::new();
+        api_client
+            .expect_create_project()
+            .times(1)
+            .returning(|_, _, _, _| Ok(project(20)));
+        api_client
+            .expect_create_task()
+            .withf(|_, project, name| project.id == 20 && name == "Design")
+            .times(1)
+            .returning(|workspace_id, project, name| {
+                Ok(Task {
+                    id: 30,
+                    name,
+                    workspace_id,
+                    project,
+                })
+            });
+        api_client
+            .expect_create_time_entry()
+            .withf(|te| {
+                te.project.as_ref().is_some_and(|p| p.id == 20)
+                    && te.task.as_ref().is_some_and(|t| t.id == 30)
+            })
+            .times(1)
+            .returning(|_| Ok(40));
+
+        UndoCommand::execute(api_client, journal, 2, false, true, true)
+            .await
+            .unwrap();
+    }
+}
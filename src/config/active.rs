@@ -10,3 +10,11 @@ impl ConfigActiveCommand {
         Ok(())
     }
 }
+
+/// The workspace the directory config sets for the current branch, if any.
+pub fn directory_workspace() -> Option<String> {
+    let config_path = super::locate::locate_config_path().ok()?;
+    let track_config = super::parser::get_config_from_file(config_path).ok()?;
+    let active = track_config.get_active_config().ok()?;
+    active.workspace.clone()
+}
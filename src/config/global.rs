@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, Offset, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ConfigError;
+use crate::models::ResultWithDefaultError;
+use crate::utilities;
+
+static GLOBAL_CONFIG: OnceLock<GlobalConfig> = OnceLock::new();
+
+/// How durations are shown in lists, pickers and previews.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DurationFormat {
+    /// `1:30:00`
+    Clock,
+    /// `1h 30m`
+    HoursMinutes,
+    /// `1.50`
+    Decimal,
+}
+
+impl DurationFormat {
+    pub fn format(&self, duration: Duration) -> String {
+        match self {
+            DurationFormat::Clock => format!(
+                "{}:{:02}:{:02}",
+                duration.num_hours(),
+                duration.num_minutes() % 60,
+                duration.num_seconds() % 60
+            ),
+            DurationFormat::HoursMinutes => {
+                format!(
+                    "{}h {:02}m",
+                    duration.num_hours(),
+                    duration.num_minutes() % 60
+                )
+            }
+            DurationFormat::Decimal => {
+                format!("{:.2}", duration.num_seconds() as f64 / 3600.0)
+            }
+        }
+    }
+}
+
+/// Defaults for every directory and profile, read from `config.toml` in the
+/// config directory. The profile, the directory config, environment variables
+/// and flags all take precedence over it.
+///
+/// ```toml
+/// workspace = "Acme Corp"
+/// picker = "fzf" # or "skim", "menu", a name from picker_commands or a command line
+/// timezone = "Europe/Berlin" # shows and reads times here instead of the system timezone
+/// week_start = "sunday" # defaults to monday
+/// duration_format = "decimal" # "clock" (1:30:00), "hours-minutes" (1h 30m) or "decimal" (1.50)
+/// color = "never" # "auto", "always" or "never"
+/// list_count = 20
+/// proxy = "http://proxy.example.com:8080"
+///
+/// [picker_commands]
+/// rofi = "rofi -dmenu -i -p Toggl"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct GlobalConfig {
+    pub workspace: Option<String>,
+    pub picker: Option<String>,
+    pub timezone: Option<String>,
+    pub week_start: Option<String>,
+    pub duration_format: Option<String>,
+    pub color: Option<String>,
+    pub list_count: Option<usize>,
+    pub proxy: Option<String>,
+    #[serde(default)]
+    pub picker_commands: HashMap<String, String>,
+}
+
+fn invalid(setting: &str, value: &str, expected: &str) -> Box<dyn std::error::Error + Send> {
+    Box::new(ConfigError::InvalidSetting(
+        setting.to_string(),
+        value.to_string(),
+        expected.to_string(),
+    ))
+}
+
+impl GlobalConfig {
+    /// Checks the settings that `toml` can't, so that a typo is reported
+    /// instead of silently falling back to the default.
+    pub fn validate(&self) -> ResultWithDefaultError<()> {
+        self.parse_timezone()?;
+        self.parse_week_start()?;
+        self.parse_duration_format()?;
+        self.parse_color()?;
+        Ok(())
+    }
+
+    fn parse_timezone(&self) -> ResultWithDefaultError<Option<Tz>> {
+        match &self.timezone {
+            None => Ok(None),
+            Some(timezone) => timezone
+                .parse::<Tz>()
+                .map(Some)
+                .map_err(|_| invalid("timezone", timezone, "a timezone such as Europe/Berlin")),
+        }
+    }
+
+    fn parse_week_start(&self) -> ResultWithDefaultError<Weekday> {
+        match &self.week_start {
+            None => Ok(Weekday::Mon),
+            Some(day) => day
+                .parse::<Weekday>()
+                .map_err(|_| invalid("week_start", day, "a day of the week such as monday")),
+        }
+    }
+
+    fn parse_duration_format(&self) -> ResultWithDefaultError<DurationFormat> {
+        match self.duration_format.as_deref() {
+            None | Some("clock") => Ok(DurationFormat::Clock),
+            Some("hours-minutes") => Ok(DurationFormat::HoursMinutes),
+            Some("decimal") => Ok(DurationFormat::Decimal),
+            Some(format) => Err(invalid(
+                "duration_format",
+                format,
+                "clock, hours-minutes or decimal",
+            )),
+        }
+    }
+
+    fn parse_color(&self) -> ResultWithDefaultError<Option<bool>> {
+        match self.color.as_deref() {
+            None | Some("auto") => Ok(None),
+            Some("always") => Ok(Some(true)),
+            Some("never") => Ok(Some(false)),
+            Some(color) => Err(invalid("color", color, "auto, always or never")),
+        }
+    }
+
+    /// The timezone times are shown and read in, when it isn't the system's.
+    pub fn timezone(&self) -> Option<Tz> {
+        self.parse_timezone().ok().flatten()
+    }
+
+    pub fn week_start(&self) -> Weekday {
+        self.parse_week_start().unwrap_or(Weekday::Mon)
+    }
+
+    pub fn duration_format(&self) -> DurationFormat {
+        self.parse_duration_format()
+            .unwrap_or(DurationFormat::Clock)
+    }
+
+    /// Whether to force colours on or off, `None` leaving it to the terminal.
+    pub fn color(&self) -> Option<bool> {
+        self.parse_color().ok().flatten()
+    }
+}
+
+impl std::fmt::Display for GlobalConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let or_default = |value: &Option<String>, default: &str| {
+            value.clone().unwrap_or(default.purple().to_string())
+        };
+        write!(
+            f,
+            "{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n",
+            "workspace".green(),
+            or_default(&self.workspace, "default"),
+            "picker".green(),
+            or_default(&self.picker, "default"),
+            "timezone".green(),
+            or_default(&self.timezone, "system"),
+            "week_start".green(),
+            or_default(&self.week_start, "monday"),
+            "duration_format".green(),
+            or_default(&self.duration_format, "clock"),
+            "color".green(),
+            or_default(&self.color, "auto"),
+            "list_count".green(),
+            self.list_count
+                .map_or("all".purple().to_string(), |count| count.to_string()),
+            "proxy".green(),
+            or_default(&self.proxy, "none"),
+            "picker_commands".green(),
+            if self.picker_commands.is_empty() {
+                "none".purple().to_string()
+            } else {
+                let mut names: Vec<&String> = self.picker_commands.keys().collect();
+                names.sort();
+                names
+                    .into_iter()
+                    .map(String::as_str)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            },
+        )
+    }
+}
+
+pub fn get_global_config_path() -> PathBuf {
+    super::locate::get_config_root().join("config.toml")
+}
+
+pub fn get_global_config() -> ResultWithDefaultError<GlobalConfig> {
+    let path = get_global_config_path();
+    if !path.exists() {
+        return Ok(GlobalConfig::default());
+    }
+    let contents = fs::read_to_string(path).map_err(|_| -> Box<dyn std::error::Error + Send> {
+        Box::new(ConfigError::FileNotFound)
+    })?;
+    let config: GlobalConfig =
+        toml::from_str(&contents).map_err(|e| -> Box<dyn std::error::Error + Send> {
+            Box::new(ConfigError::GlobalParse(e.message().to_string()))
+        })?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Makes the display preferences of `config` apply to the rest of the run.
+pub fn install(config: GlobalConfig) {
+    let _ = GLOBAL_CONFIG.set(config);
+}
+
+/// The installed global config, or the defaults before one is installed.
+pub fn get() -> &'static GlobalConfig {
+    GLOBAL_CONFIG.get_or_init(GlobalConfig::default)
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    get().duration_format().format(duration)
+}
+
+/// `time` in the configured timezone, or the system's.
+pub fn local_time(time: DateTime<Utc>) -> DateTime<FixedOffset> {
+    let offset = match get().timezone() {
+        Some(timezone) => timezone.offset_from_utc_datetime(&time.naive_utc()).fix(),
+        None => Local.offset_from_utc_datetime(&time.naive_utc()).fix(),
+    };
+    time.with_timezone(&offset)
+}
+
+/// Midnight of the first day of the week `now` falls in.
+pub fn start_of_week(now: DateTime<Utc>) -> DateTime<Utc> {
+    let now = local_time(now);
+    let today = now.date_naive();
+    let days_into_week = (7 + today.weekday().num_days_from_monday()
+        - get().week_start().num_days_from_monday())
+        % 7;
+    (today - Duration::days(days_into_week as i64))
+        .and_hms_opt(0, 0, 0)
+        .and_then(|midnight| midnight.and_local_timezone(*now.offset()).earliest())
+        .map_or(now.with_timezone(&Utc), |start| start.with_timezone(&Utc))
+}
+
+pub struct ConfigGlobalCommand;
+
+impl ConfigGlobalCommand {
+    pub async fn execute(edit: bool, validate: bool) -> ResultWithDefaultError<()> {
+        let path = get_global_config_path();
+        let display_path = utilities::simplify_config_path_for_display(path.as_path());
+        if edit {
+            if !path.exists() {
+                let config_dir = path.parent().unwrap();
+                fs::create_dir_all(config_dir).expect("failed to create config directory");
+                fs::write(&path, include_bytes!("./fixtures/global.toml"))
+                    .expect("failed to write global config");
+            }
+            utilities::open_path_in_editor(&path)?;
+            get_global_config()?;
+            return Ok(());
+        }
+
+        let config = get_global_config()?;
+        if validate {
+            println!("{} {}", "Global config is valid:".green(), display_path);
+            return Ok(());
+        }
+        println!("{config}");
+        println!("{} {}", "Global config is read from".yellow(), display_path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_are_checked_and_unknown_keys_rejected() {
+        let config: GlobalConfig =
+            toml::from_str("week_start = \"sunday\"\nduration_format = \"decimal\"").unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.week_start(), Weekday::Sun);
+        assert_eq!(
+            config.duration_format().format(Duration::minutes(90)),
+            "1.50"
+        );
+
+        let config: GlobalConfig = toml::from_str("color = \"sometimes\"").unwrap();
+        assert!(config.validate().is_err());
+        assert!(toml::from_str::<GlobalConfig>("list_cuont = 20").is_err());
+    }
+}
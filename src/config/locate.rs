@@ -45,7 +45,7 @@ pub fn get_config_path_for_current_dir() -> ResultWithDefaultError<PathBuf> {
     Ok(get_encoded_config_path(&config_root, &path))
 }
 
-fn get_config_root() -> PathBuf {
+pub fn get_config_root() -> PathBuf {
     directories::ProjectDirs::from("studio.watercooler", "labs", "toggl-cli")
         .unwrap()
         .config_local_dir()
@@ -1,6 +1,8 @@
 pub mod active;
+pub mod global;
 pub mod init;
 pub mod locate;
 pub mod manage;
 pub mod model;
 pub mod parser;
+pub mod profile;
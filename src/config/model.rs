@@ -10,7 +10,8 @@ use crate::models::{Entities, ResultWithDefaultError, TimeEntry};
 use crate::utilities;
 
 /// BranchConfig optionally determines workspace, description, project, task,
-/// tags, and billable status of a time entry.
+/// tags, and billable status of a time entry, and the profile used to talk to
+/// Toggl.
 /// The fields are optional, and if not specified, the default values will be
 /// used. The string fields support templating, which will be replaced with live
 /// values on deserialization.
@@ -40,6 +41,7 @@ use crate::utilities;
 /// task = "Development"
 /// tags = ["{{branch}}", "{{$ date +\"%Y\"}}"]
 /// billable = true
+/// profile = "acme"
 /// ```
 ///
 /// and the following shell state:
@@ -54,7 +56,7 @@ use crate::utilities;
 /// 2023
 /// ```
 ///
-/// the following time entry will be created:
+/// the following time entry will be created with the `acme` profile:
 /// ```json
 /// {
 ///  "workspace": "Default",
@@ -73,6 +75,7 @@ pub struct BranchConfig {
     pub task: Option<String>,
     pub tags: Option<Vec<String>>,
     pub billable: bool,
+    pub profile: Option<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -87,8 +90,17 @@ const PROJECT: &str = "project";
 const TASK: &str = "task";
 const TAGS: &str = "tags";
 const BILLABLE: &str = "billable";
-
-const FIELDS: &[&str] = &[WORKSPACE, DESCRIPTION, PROJECT, TASK, TAGS, BILLABLE];
+const PROFILE: &str = "profile";
+
+const FIELDS: &[&str] = &[
+    WORKSPACE,
+    DESCRIPTION,
+    PROJECT,
+    TASK,
+    TAGS,
+    BILLABLE,
+    PROFILE,
+];
 
 impl<'de> Deserialize<'de> for BranchConfig {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -118,6 +130,7 @@ impl<'de> Deserialize<'de> for BranchConfig {
                 let mut task: Option<String> = None;
                 let mut tags: Option<Vec<String>> = None;
                 let mut billable: Option<bool> = None;
+                let mut profile: Option<String> = None;
                 while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
                         WORKSPACE => {
@@ -140,6 +153,9 @@ impl<'de> Deserialize<'de> for BranchConfig {
                         BILLABLE => {
                             billable = Some(map.next_value()?);
                         }
+                        PROFILE => {
+                            profile = Some(map.next_value()?);
+                        }
                         _ => {
                             return Err(de::Error::unknown_field(&key, FIELDS));
                         }
@@ -152,6 +168,7 @@ impl<'de> Deserialize<'de> for BranchConfig {
                     task,
                     tags,
                     billable: billable.unwrap_or(false),
+                    profile,
                 })
             }
         }
@@ -171,7 +188,7 @@ impl<'de> Deserialize<'de> for BranchConfig {
 impl std::fmt::Display for BranchConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let summary = format!(
-            "{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n",
+            "{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n",
             WORKSPACE.green(),
             self.workspace
                 .as_ref()
@@ -196,6 +213,10 @@ impl std::fmt::Display for BranchConfig {
                 .unwrap_or("[]".yellow().to_string()),
             BILLABLE.green(),
             self.billable,
+            PROFILE.green(),
+            self.profile
+                .as_ref()
+                .unwrap_or(&"default".purple().to_string()),
         );
         write!(f, "{summary}")
     }
@@ -546,10 +567,11 @@ impl TrackConfig {
             // Default to -1 if workspace is not set
             Ok(-1),
             |name| {
-                entities.workspace_id_for_name(name).ok_or_else(|| {
-                    Box::new(ConfigError::WorkspaceNotFound(name.clone()))
-                        as Box<dyn std::error::Error + Send>
-                })
+                if !entities.workspaces.iter().any(|w| w.name == *name) {
+                    return Err(Box::new(ConfigError::WorkspaceNotFound(name.clone()))
+                        as Box<dyn std::error::Error + Send>);
+                }
+                entities.workspace_id_for_name(name)
             },
         )?;
 
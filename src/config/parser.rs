@@ -1,13 +1,18 @@
 use std::path::Path;
 use toml;
 
+use crate::error::ConfigError;
 use crate::models::ResultWithDefaultError;
 
 use super::model::TrackConfig;
 
 pub fn get_config_from_file<P: AsRef<Path>>(path: P) -> ResultWithDefaultError<TrackConfig> {
-    let contents = std::fs::read_to_string(path).expect("failed to read config file");
-    let config: TrackConfig = toml::from_str(&contents).expect("failed to parse config");
+    let contents =
+        std::fs::read_to_string(path).map_err(|_| -> Box<dyn std::error::Error + Send> {
+            Box::new(ConfigError::FileNotFound)
+        })?;
+    let config: TrackConfig = toml::from_str(&contents)
+        .map_err(|_| -> Box<dyn std::error::Error + Send> { Box::new(ConfigError::Parse) })?;
 
     Ok(config)
 }
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::constants;
+use crate::error::{ArgumentError, ConfigError};
+use crate::models::ResultWithDefaultError;
+use crate::utilities;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Per-profile preferences, read from the `[<profile>]` table of
+/// `profiles.toml` in the config directory.
+///
+/// ```toml
+/// [acme]
+/// workspace = "Acme Corp"
+/// picker = "fzf" # or "skim", "menu", a name from picker_commands or a command line
+/// json = true
+/// credentials = "file" # or "keyring", "encrypted-file"
+/// token_command = "pass show toggl/api"
+/// journal_retention_days = 30 # 0 turns the undo journal off
+///
+/// [acme.picker_commands]
+/// rofi = "rofi -dmenu -i -p Toggl"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProfileConfig {
+    pub workspace: Option<String>,
+    pub picker: Option<String>,
+    pub json: Option<bool>,
+    pub credentials: Option<String>,
+    pub token_command: Option<String>,
+    pub journal_retention_days: Option<i64>,
+    #[serde(default)]
+    pub picker_commands: HashMap<String, String>,
+}
+
+impl ProfileConfig {
+    pub fn journal_retention_days(&self) -> i64 {
+        self.journal_retention_days
+            .unwrap_or(constants::JOURNAL_RETENTION_DAYS)
+    }
+}
+
+impl std::fmt::Display for ProfileConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n",
+            "workspace".green(),
+            self.workspace
+                .as_ref()
+                .unwrap_or(&"default".purple().to_string()),
+            "picker".green(),
+            self.picker
+                .as_ref()
+                .unwrap_or(&"default".purple().to_string()),
+            "json".green(),
+            self.json.unwrap_or(false),
+            "credentials".green(),
+            self.credentials
+                .as_ref()
+                .unwrap_or(&"default".purple().to_string()),
+            "token_command".green(),
+            self.token_command
+                .as_ref()
+                .unwrap_or(&"none".purple().to_string()),
+            "journal_retention_days".green(),
+            self.journal_retention_days(),
+            "picker_commands".green(),
+            if self.picker_commands.is_empty() {
+                "none".purple().to_string()
+            } else {
+                let mut names: Vec<&String> = self.picker_commands.keys().collect();
+                names.sort();
+                names
+                    .into_iter()
+                    .map(String::as_str)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            },
+        )
+    }
+}
+
+pub fn get_profiles_path() -> PathBuf {
+    super::locate::get_config_root().join("profiles.toml")
+}
+
+/// Picks the profile to use: the `--profile` flag or `TOGGL_PROFILE` first,
+/// then the profile pinned by the directory config, then the default profile.
+/// The name ends up in file paths, so it can't leave the directories it
+/// names a file in.
+pub fn resolve_profile_name(profile: Option<String>) -> ResultWithDefaultError<String> {
+    let name = profile
+        .or_else(|| {
+            super::locate::locate_config_path()
+                .and_then(super::parser::get_config_from_file)
+                .ok()
+                .and_then(|track_config| {
+                    track_config
+                        .get_active_config()
+                        .ok()
+                        .and_then(|config| config.profile.clone())
+                })
+        })
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+    if !is_valid_profile_name(&name) {
+        return Err(Box::new(ArgumentError::InvalidProfileName(name)));
+    }
+    Ok(name)
+}
+
+fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['/', '\\']) && !name.contains("..")
+}
+
+pub fn get_profile_config(name: &str) -> ResultWithDefaultError<ProfileConfig> {
+    let path = get_profiles_path();
+    if !path.exists() {
+        return Ok(ProfileConfig::default());
+    }
+    let contents = fs::read_to_string(path).map_err(|_| -> Box<dyn std::error::Error + Send> {
+        Box::new(ConfigError::FileNotFound)
+    })?;
+    let mut profiles: HashMap<String, ProfileConfig> =
+        toml::from_str(&contents).map_err(|e| -> Box<dyn std::error::Error + Send> {
+            Box::new(ConfigError::ProfilesParse(e.message().to_string()))
+        })?;
+    Ok(profiles.remove(name).unwrap_or_default())
+}
+
+pub struct ConfigProfileCommand;
+
+impl ConfigProfileCommand {
+    pub async fn execute(name: &str, edit: bool) -> ResultWithDefaultError<()> {
+        let path = get_profiles_path();
+        if edit {
+            if !path.exists() {
+                let config_dir = path.parent().unwrap();
+                fs::create_dir_all(config_dir).expect("failed to create config directory");
+                fs::write(&path, format!("[{name}]\n")).expect("failed to write profiles file");
+            }
+            return utilities::open_path_in_editor(path);
+        }
+
+        let profile = get_profile_config(name)?;
+        println!("{}\n{}", format!("[{name}]").purple().bold(), profile);
+        println!(
+            "{} {}",
+            "Profiles are read from".yellow(),
+            utilities::simplify_config_path_for_display(path.as_path())
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_names_that_would_leave_their_directory_are_rejected() {
+        assert!(is_valid_profile_name("acme"));
+        assert!(is_valid_profile_name("acme.dev"));
+        for name in ["", "../acme", "acme/dev", "acme\\dev", ".."] {
+            assert!(!is_valid_profile_name(name), "{name}");
+        }
+        assert!(resolve_profile_name(Some("../../etc".to_string())).is_err());
+    }
+}
@@ -4,6 +4,15 @@ pub const CLIENT_NAME: &str = "github.com/watercooler-labs/toggl-cli/toggl-cli";
 pub const GENERIC_ERROR: &str = "Something went wrong.";
 pub const NETWORK_ERROR_MESSAGE: &str =
     "An error occurred when making a network request\nCheck your connection and try again.";
+pub const UNAUTHORIZED_ERROR_MESSAGE: &str =
+    "Your API token was rejected. Run toggl auth to set a new one.";
+pub const API_NOT_FOUND_ERROR_MESSAGE: &str =
+    "The requested item doesn't exist or was already deleted.";
+pub const API_FORBIDDEN_ERROR_MESSAGE: &str =
+    "Your account doesn't have permission for this workspace, project or item.";
+pub const API_RATE_LIMITED_ERROR_MESSAGE: &str =
+    "Toggl is limiting how many requests you can make, wait a minute and try again.";
+pub const API_STATUS_ERROR_MESSAGE: &str = "Toggl couldn't handle the request, it answered";
 pub const DESERIALIZATION_ERROR_MESSAGE: &str = "An error occurred when making a network request.";
 pub const ISSUE_LINK: &str = "https://github.com/watercooler-labs/toggl-cli/issues/new";
 pub const CREDENTIALS_ACCESS_ERROR: &str =
@@ -18,20 +27,61 @@ pub const CREDENTIALS_FIND_TOKEN_MESSAGE: &str = "You can find your API token at
 pub const CREDENTIALS_FIND_TOKEN_LINK: &str = "https://track.toggl.com/profile";
 pub const CREDENTIALS_OVERRIDE_ERROR: &str =
     "You have set TOGGL_API_TOKEN in your environment. Unset it to update or delete your saved credentials.";
+pub const CREDENTIALS_COMMAND_OVERRIDE_ERROR: &str =
+    "Your API token is read from token_command (or TOGGL_TOKEN_COMMAND). Update or delete it in your password manager instead.";
+pub const CREDENTIALS_TOKEN_COMMAND_ERROR: &str = "Couldn't read your API token from";
+pub const CREDENTIALS_PERMISSIONS_ERROR: &str =
+    "Your credentials file can be read by other users, refusing to use it";
+pub const CREDENTIALS_DECRYPT_ERROR: &str =
+    "Couldn't decrypt your credentials file. Check your passphrase.";
+pub const CREDENTIALS_UNKNOWN_BACKEND_ERROR: &str = "Unknown credentials storage";
 
 pub const FZF_NOT_INSTALLED_ERROR: &str = "fzf could not be found. Is it installed?";
+pub const PICKER_COMMAND_ERROR: &str = "Picker command failed";
 pub const OPERATION_CANCELLED: &str = "Operation cancelled";
 pub const CONFIG_FILE_NOT_FOUND_ERROR: &str = "No config file found";
 pub const CONFIG_PARSE_ERROR: &str = "Failed to parse config file";
 pub const CONFIG_UNRECOGNIZED_MACRO_ERROR: &str = "Unrecognized macro in config file";
 pub const CONFIG_SHELL_MACRO_RESOLUTION_ERROR: &str = "Failed to resolve shell macro";
 pub const CONFIG_INVALID_WORKSPACE_ERROR: &str = "Workspace not found";
+pub const GLOBAL_CONFIG_PARSE_ERROR: &str = "Failed to parse global config file";
+pub const GLOBAL_CONFIG_INVALID_SETTING_ERROR: &str = "Invalid setting in global config file";
+pub const PROFILES_PARSE_ERROR: &str = "Failed to parse profiles file";
+pub const INVALID_PROFILE_NAME_ERROR: &str = "Profile names can't contain '/', '\\' or '..'";
+pub const ALL_WORKSPACES_CONFLICT_ERROR: &str =
+    "--all-workspaces lists every workspace and can't be combined with --workspace";
+pub const AMBIGUOUS_WORKSPACE_ERROR: &str = "More than one workspace is named";
+pub const TIME_ENTRIES_WINDOW_DAYS: i64 = 30;
+pub const MAX_CONCURRENT_REQUESTS: usize = 4;
+pub const MAX_REQUEST_RETRIES: u32 = 4;
+pub const RETRY_BASE_DELAY_SECONDS: u64 = 1;
+pub const PROGRESS_WINDOW_THRESHOLD: usize = 3;
 pub const NO_PROJECT: &str = "No Project";
 pub const NO_TASK: &str = "No Task";
 pub const DEFAULT_ENTITY_ID: i64 = -1;
 pub const NO_DESCRIPTION: &str = "(no description)";
+pub const INVALID_TIMESTAMP_ERROR: &str = "is not a valid RFC3339 timestamp";
+pub const INVALID_DATE_ERROR: &str = "is not a valid date, expected YYYY-MM-DD";
+pub const CONFIRMATION_REQUIRED_ERROR: &str =
+    "Refusing to go ahead without confirmation, pass --yes when stdin is not a terminal";
+pub const AMBIGUOUS_NAME_ERROR: &str = "More than one match for";
+pub const LOOSE_MATCH_ERROR: &str = "No name starts with";
+pub const TASK_NOT_IN_PROJECT_ERROR: &str = "it belongs to project";
+pub const TASK_NEEDS_PROJECT_ERROR: &str = "A new task needs a project, pass --project as well";
+pub const ACTION_NEEDS_TIME_ENTRY_ERROR: &str = "This action only works on time entries";
+pub const DEFAULT_PROJECT_COLOR: &str = "#06aaf5";
 pub const DIRECTORY_NOT_FOUND_ERROR: &str = "Directory not found";
 pub const NOT_A_DIRECTORY_ERROR: &str = "Not a directory";
+pub const ARCHIVE_READ_ERROR: &str = "Couldn't read backup archive";
+pub const ARCHIVE_WRITE_ERROR: &str = "Couldn't write backup archive";
+pub const ARCHIVE_UNSUPPORTED_VERSION_ERROR: &str =
+    "Backup archive was written by a newer version of the app, archive version";
+pub const BACKUP_EARLIEST_DATE: &str = "2006-01-01";
+pub const JOURNAL_RETENTION_DAYS: i64 = 30;
+pub const JOURNAL_READ_ERROR: &str = "Couldn't read the operation journal";
+pub const JOURNAL_WRITE_ERROR: &str = "Couldn't write the operation journal";
+pub const JOURNAL_NOTHING_TO_UNDO_ERROR: &str = "Nothing to undo";
+pub const JOURNAL_MISSING_STATE_ERROR: &str = "Can't undo, the previous state wasn't recorded for";
 
 #[cfg(target_os = "macos")]
 pub const SIMPLE_HOME_PATH: &str = "~/Library/Application Support";
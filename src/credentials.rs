@@ -1,8 +1,23 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config;
+use crate::config::profile::ProfileConfig;
 use crate::error;
 use crate::models;
+use crate::utilities;
+use argon2::Argon2;
 use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
 use error::StorageError;
 use keyring::Entry;
+use lazy_static::lazy_static;
 #[cfg(test)]
 use mockall::automock;
 use models::ResultWithDefaultError;
@@ -17,6 +32,8 @@ pub trait CredentialsStorage {
     fn read(&self) -> ResultWithDefaultError<Credentials>;
     fn persist(&self, api_token: String) -> ResultWithDefaultError<()>;
     fn clear(&self) -> ResultWithDefaultError<()>;
+    /// Human readable name of the backend, e.g. for `toggl auth status`.
+    fn describe(&self) -> String;
 }
 
 pub struct KeyringStorage {
@@ -59,6 +76,10 @@ impl CredentialsStorage for KeyringStorage {
             Ok(_) => Ok(()),
         }
     }
+
+    fn describe(&self) -> String {
+        "keyring".to_string()
+    }
 }
 
 pub struct EnvironmentStorage {
@@ -83,14 +104,341 @@ impl CredentialsStorage for EnvironmentStorage {
     fn clear(&self) -> ResultWithDefaultError<()> {
         Err(Box::new(StorageError::EnvironmentOverride))
     }
+    fn describe(&self) -> String {
+        "environment (TOGGL_API_TOKEN)".to_string()
+    }
+}
+
+lazy_static! {
+    /// Tokens printed by token commands, by command, so that a password
+    /// manager prompt shows up once per process however often the storage is
+    /// looked up.
+    static ref COMMAND_TOKENS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// Reads the token from the output of a user supplied command, such as
+/// `pass show toggl/api`, so it can live in a password manager. The command
+/// runs at most once per process.
+pub struct CommandStorage {
+    command: String,
+}
+
+impl CommandStorage {
+    pub fn new(command: String) -> CommandStorage {
+        Self { command }
+    }
+
+    fn run(&self) -> ResultWithDefaultError<String> {
+        let output = utilities::get_shell_cmd(&self.command)
+            .stdin(std::process::Stdio::inherit())
+            .stderr(std::process::Stdio::inherit())
+            .output()
+            .map_err(|e| -> Box<dyn std::error::Error + Send> {
+                Box::new(StorageError::TokenCommand(
+                    self.command.clone(),
+                    e.to_string(),
+                ))
+            })?;
+        if !output.status.success() {
+            return Err(Box::new(StorageError::TokenCommand(
+                self.command.clone(),
+                format!("exited with {}", output.status),
+            )));
+        }
+        let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if token.is_empty() {
+            return Err(Box::new(StorageError::TokenCommand(
+                self.command.clone(),
+                "printed no token".to_string(),
+            )));
+        }
+        Ok(token)
+    }
+}
+
+impl CredentialsStorage for CommandStorage {
+    fn read(&self) -> ResultWithDefaultError<Credentials> {
+        // Held while the command runs, so concurrent reads don't prompt twice
+        let mut tokens = COMMAND_TOKENS.lock().unwrap();
+        let api_token = match tokens.get(&self.command) {
+            Some(api_token) => api_token.clone(),
+            None => {
+                let api_token = self.run()?;
+                tokens.insert(self.command.clone(), api_token.clone());
+                api_token
+            }
+        };
+        Ok(Credentials { api_token })
+    }
+    fn persist(&self, _api_token: String) -> ResultWithDefaultError<()> {
+        Err(Box::new(StorageError::CommandOverride))
+    }
+    fn clear(&self) -> ResultWithDefaultError<()> {
+        Err(Box::new(StorageError::CommandOverride))
+    }
+    fn describe(&self) -> String {
+        format!("command ({})", self.command)
+    }
+}
+
+const ENCRYPTED_PREFIX: &str = "encrypted:v1:";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+/// Stores the token in a file that only the current user can read, for
+/// machines without a usable keyring such as containers, CI runners and SSH
+/// sessions. When `encrypt` is set the token is sealed with a key derived from
+/// a passphrase, taken from `TOGGL_CREDENTIALS_PASSPHRASE` or prompted for.
+pub struct FileStorage {
+    path: PathBuf,
+    encrypt: bool,
+}
+
+impl FileStorage {
+    pub fn new(path: PathBuf, encrypt: bool) -> FileStorage {
+        Self { path, encrypt }
+    }
+
+    pub fn path_for_profile(profile: &str) -> PathBuf {
+        config::locate::get_config_root()
+            .join("credentials")
+            .join(profile)
+    }
+
+    fn passphrase() -> ResultWithDefaultError<String> {
+        if let Ok(passphrase) = std::env::var("TOGGL_CREDENTIALS_PASSPHRASE") {
+            return Ok(passphrase);
+        }
+        rpassword::prompt_password("Credentials passphrase: ")
+            .map_err(|_| -> Box<dyn std::error::Error + Send> { Box::new(StorageError::Read) })
+    }
+
+    #[cfg(unix)]
+    fn check_permissions(&self) -> ResultWithDefaultError<()> {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&self.path)
+            .map_err(|_| -> Box<dyn std::error::Error + Send> { Box::new(StorageError::Read) })?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            return Err(Box::new(StorageError::InsecurePermissions(
+                self.path.clone(),
+            )));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn check_permissions(&self) -> ResultWithDefaultError<()> {
+        Ok(())
+    }
+
+    fn open_for_write(&self) -> std::io::Result<fs::File> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            if self.path.exists() {
+                fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        options.open(&self.path)
+    }
 }
 
-pub fn get_storage() -> Box<dyn CredentialsStorage> {
+impl CredentialsStorage for FileStorage {
+    fn read(&self) -> ResultWithDefaultError<Credentials> {
+        if !self.path.exists() {
+            return Err(Box::new(StorageError::Read));
+        }
+        self.check_permissions()?;
+        let contents =
+            fs::read_to_string(&self.path).map_err(|_| -> Box<dyn std::error::Error + Send> {
+                Box::new(StorageError::Unknown)
+            })?;
+        let contents = contents.trim();
+        let api_token = match contents.strip_prefix(ENCRYPTED_PREFIX) {
+            Some(sealed) => decrypt_token(sealed, &FileStorage::passphrase()?)?,
+            None => contents.to_string(),
+        };
+        if api_token.is_empty() {
+            return Err(Box::new(StorageError::Read));
+        }
+        Ok(Credentials { api_token })
+    }
+
+    fn persist(&self, api_token: String) -> ResultWithDefaultError<()> {
+        let contents = if self.encrypt {
+            format!(
+                "{ENCRYPTED_PREFIX}{}",
+                encrypt_token(&api_token, &FileStorage::passphrase()?)?
+            )
+        } else {
+            api_token
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|_| -> Box<dyn std::error::Error + Send> {
+                Box::new(StorageError::Write)
+            })?;
+        }
+        self.open_for_write()
+            .and_then(|mut file| file.write_all(contents.as_bytes()))
+            .map_err(|file_err| -> Box<dyn std::error::Error + Send> {
+                eprintln!("Error writing credentials file: {file_err}");
+                Box::new(StorageError::Write)
+            })
+    }
+
+    fn clear(&self) -> ResultWithDefaultError<()> {
+        fs::remove_file(&self.path).map_err(|file_err| -> Box<dyn std::error::Error + Send> {
+            eprintln!("Error deleting credentials file: {file_err}");
+            Box::new(StorageError::Delete)
+        })
+    }
+
+    fn describe(&self) -> String {
+        let encrypted = self.encrypt
+            || fs::read_to_string(&self.path)
+                .map(|contents| contents.starts_with(ENCRYPTED_PREFIX))
+                .unwrap_or(false);
+        format!(
+            "file ({}{})",
+            self.path.display(),
+            if encrypted { ", encrypted" } else { "" }
+        )
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> ResultWithDefaultError<Key> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|_| -> Box<dyn std::error::Error + Send> { Box::new(StorageError::Decrypt) })?;
+    Ok(key.into())
+}
+
+fn encrypt_token(api_token: &str, passphrase: &str) -> ResultWithDefaultError<String> {
+    let mut salt = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, api_token.as_bytes())
+        .map_err(|_| -> Box<dyn std::error::Error + Send> { Box::new(StorageError::Write) })?;
+
+    let mut sealed = salt.to_vec();
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(general_purpose::STANDARD.encode(sealed))
+}
+
+fn decrypt_token(sealed: &str, passphrase: &str) -> ResultWithDefaultError<String> {
+    let decrypt_error = || -> Box<dyn std::error::Error + Send> { Box::new(StorageError::Decrypt) };
+    let sealed = general_purpose::STANDARD
+        .decode(sealed)
+        .map_err(|_| decrypt_error())?;
+    if sealed.len() <= SALT_LENGTH + NONCE_LENGTH {
+        return Err(decrypt_error());
+    }
+    let (salt, rest) = sealed.split_at(SALT_LENGTH);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LENGTH);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| decrypt_error())?;
+    String::from_utf8(plaintext).map_err(|_| decrypt_error())
+}
+
+/// Picks the credentials backend for a profile. `TOGGL_API_TOKEN` always wins,
+/// then a `token_command` from `TOGGL_TOKEN_COMMAND` or the profile, then the
+/// backend chosen with `TOGGL_CREDENTIALS_STORAGE` or the profile's
+/// `credentials` setting (`keyring`, `file` or `encrypted-file`). Without an
+/// explicit choice the keyring is used, falling back to the credentials file
+/// when the keyring is unavailable.
+pub fn get_storage(
+    profile: &str,
+    profile_config: &ProfileConfig,
+) -> ResultWithDefaultError<Box<dyn CredentialsStorage>> {
     if let Ok(api_token) = std::env::var("TOGGL_API_TOKEN") {
-        return Box::new(EnvironmentStorage::new(api_token));
+        return Ok(Box::new(EnvironmentStorage::new(api_token)));
+    }
+    let token_command = std::env::var("TOGGL_TOKEN_COMMAND")
+        .ok()
+        .or(profile_config.token_command.clone())
+        .filter(|command| !command.trim().is_empty());
+    if let Some(command) = token_command {
+        return Ok(Box::new(CommandStorage::new(command)));
+    }
+    let backend = profile_config.credentials.as_deref();
+
+    let file_path = FileStorage::path_for_profile(profile);
+    let backend = std::env::var("TOGGL_CREDENTIALS_STORAGE")
+        .ok()
+        .or(backend.map(str::to_string));
+    match backend.as_deref() {
+        Some("file") => return Ok(Box::new(FileStorage::new(file_path, false))),
+        Some("encrypted-file") => return Ok(Box::new(FileStorage::new(file_path, true))),
+        Some("keyring") | None => {}
+        Some(unknown) => {
+            return Err(Box::new(StorageError::UnknownBackend(unknown.to_string())));
+        }
+    }
+
+    // Every profile gets its own keyring entry, the default profile keeps the
+    // entry used before profiles existed.
+    let keyring = match Entry::new("togglcli", profile) {
+        Ok(keyring) => keyring,
+        Err(_) => return Ok(Box::new(FileStorage::new(file_path, false))),
+    };
+    match keyring.get_password() {
+        Err(keyring::Error::PlatformFailure(_)) | Err(keyring::Error::NoStorageAccess(_)) => {
+            Ok(Box::new(FileStorage::new(file_path, false)))
+        }
+        Err(keyring::Error::NoEntry) if file_path.exists() => {
+            Ok(Box::new(FileStorage::new(file_path, false)))
+        }
+        _ => Ok(Box::new(KeyringStorage::new(keyring))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_encrypted_token_decrypts_with_the_same_passphrase() {
+        let sealed = encrypt_token("SOME_API_TOKEN_VALUE", "hunter2").unwrap();
+
+        assert_ne!(sealed, "SOME_API_TOKEN_VALUE");
+        assert_eq!(
+            decrypt_token(&sealed, "hunter2").unwrap(),
+            "SOME_API_TOKEN_VALUE"
+        );
     }
 
-    let keyring = Entry::new("togglcli", "default")
-        .unwrap_or_else(|err| panic!("Couldn't create credentials_storage: {err}"));
-    Box::new(KeyringStorage::new(keyring))
+    #[test]
+    fn an_encrypted_token_does_not_decrypt_with_a_wrong_passphrase() {
+        let sealed = encrypt_token("SOME_API_TOKEN_VALUE", "hunter2").unwrap();
+
+        assert!(decrypt_token(&sealed, "hunter3").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_token_command_runs_once_however_many_storages_read_it() {
+        let directory = tempfile::tempdir().unwrap();
+        let runs = directory.path().join("runs");
+        let command = format!(
+            "echo run >> '{}'; echo SOME_API_TOKEN_VALUE",
+            runs.display()
+        );
+
+        for _ in 0..2 {
+            let credentials = CommandStorage::new(command.clone()).read().unwrap();
+            assert_eq!(credentials.api_token, "SOME_API_TOKEN_VALUE");
+        }
+        assert_eq!(fs::read_to_string(runs).unwrap().lines().count(), 1);
+    }
 }
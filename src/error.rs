@@ -8,6 +8,10 @@ use std::path::PathBuf;
 pub enum ApiError {
     Network,
     Deserialization,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Status(u16),
 }
 
 impl Display for ApiError {
@@ -20,6 +24,25 @@ impl Display for ApiError {
                 constants::OUTDATED_APP_ERROR_MESSAGE.blue().bold(),
                 constants::ISSUE_LINK.blue().bold().underline()
             ),
+            ApiError::NotFound => format!("{}", constants::API_NOT_FOUND_ERROR_MESSAGE.red()),
+            ApiError::Forbidden => format!("{}", constants::API_FORBIDDEN_ERROR_MESSAGE.red()),
+            ApiError::Status(429) => {
+                format!("{}", constants::API_RATE_LIMITED_ERROR_MESSAGE.red())
+            }
+            ApiError::Status(status) => format!(
+                "{} {}",
+                constants::API_STATUS_ERROR_MESSAGE.red(),
+                format!("HTTP {status}").red().bold()
+            ),
+            ApiError::Unauthorized => format!(
+                "{}\n{} {}",
+                constants::UNAUTHORIZED_ERROR_MESSAGE.red(),
+                constants::CREDENTIALS_FIND_TOKEN_MESSAGE.blue().bold(),
+                constants::CREDENTIALS_FIND_TOKEN_LINK
+                    .blue()
+                    .bold()
+                    .underline()
+            ),
         };
         writeln!(f, "{summary}")
     }
@@ -34,6 +57,11 @@ pub enum StorageError {
     Delete,
     Unknown,
     EnvironmentOverride,
+    InsecurePermissions(PathBuf),
+    Decrypt,
+    UnknownBackend(String),
+    TokenCommand(String, String),
+    CommandOverride,
 }
 
 impl Display for StorageError {
@@ -66,6 +94,37 @@ impl Display for StorageError {
             StorageError::EnvironmentOverride => {
                 format!("{}", constants::CREDENTIALS_OVERRIDE_ERROR.red())
             }
+            StorageError::InsecurePermissions(path) => {
+                format!(
+                    "{}: {}\n{} {}",
+                    constants::CREDENTIALS_PERMISSIONS_ERROR.red(),
+                    path.display(),
+                    "Restrict it with".yellow(),
+                    format!("chmod 600 {}", path.display()).yellow().bold()
+                )
+            }
+            StorageError::Decrypt => {
+                format!("{}", constants::CREDENTIALS_DECRYPT_ERROR.red())
+            }
+            StorageError::TokenCommand(command, reason) => {
+                format!(
+                    "{} {}: {}",
+                    constants::CREDENTIALS_TOKEN_COMMAND_ERROR.red(),
+                    command.red().bold(),
+                    reason
+                )
+            }
+            StorageError::CommandOverride => {
+                format!("{}", constants::CREDENTIALS_COMMAND_OVERRIDE_ERROR.red())
+            }
+            StorageError::UnknownBackend(backend) => {
+                format!(
+                    "{}: {}\n{}",
+                    constants::CREDENTIALS_UNKNOWN_BACKEND_ERROR.red(),
+                    backend.red().bold(),
+                    "Valid values are: keyring, file, encrypted-file".yellow()
+                )
+            }
         };
 
         writeln!(f, "{summary}")
@@ -78,20 +137,24 @@ impl Error for StorageError {}
 pub enum PickerError {
     Cancelled,
     FzfNotInstalled,
+    CommandFailed(String, String),
     Generic,
 }
 
 impl Display for PickerError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let summary = format!(
-            "{}",
-            match self {
-                PickerError::Cancelled => constants::OPERATION_CANCELLED,
-                PickerError::FzfNotInstalled => constants::FZF_NOT_INSTALLED_ERROR,
-                PickerError::Generic => constants::GENERIC_ERROR,
-            }
-            .red(),
-        );
+        let summary = match self {
+            PickerError::CommandFailed(command, error) => format!(
+                "{}: {}\n{}: {}",
+                constants::PICKER_COMMAND_ERROR.red(),
+                error.red().bold(),
+                "Command".yellow(),
+                command.yellow().bold(),
+            ),
+            PickerError::Cancelled => constants::OPERATION_CANCELLED.red().to_string(),
+            PickerError::FzfNotInstalled => constants::FZF_NOT_INSTALLED_ERROR.red().to_string(),
+            PickerError::Generic => constants::GENERIC_ERROR.red().to_string(),
+        };
         writeln!(f, "{summary}")
     }
 }
@@ -105,6 +168,9 @@ pub enum ConfigError {
     UnrecognizedMarco(String),
     ShellResolution(String, String),
     WorkspaceNotFound(String),
+    GlobalParse(String),
+    InvalidSetting(String, String, String),
+    ProfilesParse(String),
 }
 
 impl Display for ConfigError {
@@ -149,6 +215,33 @@ impl Display for ConfigError {
                     "toggl config --edit".yellow().bold(),
                 )
             }
+            ConfigError::GlobalParse(message) => {
+                format!(
+                    "{}: {}\nTo edit the global configuration in your editor run {}",
+                    constants::GLOBAL_CONFIG_PARSE_ERROR.red().bold(),
+                    message.trim(),
+                    "toggl config global --edit".blue().bold(),
+                )
+            }
+            ConfigError::InvalidSetting(setting, value, expected) => {
+                format!(
+                    "{}: {} = \"{}\"\n{}: {}\nTo edit the global configuration in your editor run {}",
+                    constants::GLOBAL_CONFIG_INVALID_SETTING_ERROR.red().bold(),
+                    setting.red().bold(),
+                    value.red().bold(),
+                    "Expected".yellow(),
+                    expected.yellow().bold(),
+                    "toggl config global --edit".blue().bold(),
+                )
+            }
+            ConfigError::ProfilesParse(message) => {
+                format!(
+                    "{}: {}\nTo edit the profiles in your editor run {}",
+                    constants::PROFILES_PARSE_ERROR.red().bold(),
+                    message.trim(),
+                    "toggl config profile --edit".blue().bold(),
+                )
+            }
         };
         writeln!(f, "{summary}")
     }
@@ -160,6 +253,18 @@ impl Error for ConfigError {}
 pub enum ArgumentError {
     DirectoryNotFound(PathBuf),
     NotADirectory(PathBuf),
+    WorkspaceNotFound(String),
+    AmbiguousWorkspace(String, Vec<i64>),
+    InvalidTimestamp(String, String, String),
+    InvalidDate(String, String),
+    ConfirmationRequired,
+    AmbiguousName(String, String, Vec<String>),
+    LooseMatch(String, String, String),
+    TaskNeedsProject,
+    TaskNotInProject(String, String, String),
+    ActionNeedsTimeEntry(String),
+    InvalidProfileName(String),
+    AllWorkspacesWithWorkspace(String),
 }
 
 impl Display for ArgumentError {
@@ -179,9 +284,334 @@ impl Display for ArgumentError {
                     path.display()
                 )
             }
+            ArgumentError::WorkspaceNotFound(workspace) => {
+                format!(
+                    "{}: {}",
+                    constants::CONFIG_INVALID_WORKSPACE_ERROR.red(),
+                    workspace.red().bold()
+                )
+            }
+            ArgumentError::AmbiguousWorkspace(workspace, ids) => {
+                format!(
+                    "{}: {}\n{} {}",
+                    constants::AMBIGUOUS_WORKSPACE_ERROR.red(),
+                    workspace.red().bold(),
+                    "Pass one of these ids instead:".yellow(),
+                    ids.iter()
+                        .map(|id| id.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                        .yellow()
+                        .bold()
+                )
+            }
+            ArgumentError::InvalidTimestamp(field, value, reason) => {
+                format!(
+                    "{} \"{}\" {}: {}",
+                    field.red(),
+                    value.red().bold(),
+                    constants::INVALID_TIMESTAMP_ERROR.red(),
+                    reason
+                )
+            }
+            ArgumentError::InvalidDate(flag, value) => {
+                format!(
+                    "{} \"{}\" {}",
+                    flag.red(),
+                    value.red().bold(),
+                    constants::INVALID_DATE_ERROR.red()
+                )
+            }
+            ArgumentError::ConfirmationRequired => {
+                format!("{}", constants::CONFIRMATION_REQUIRED_ERROR.red())
+            }
+            ArgumentError::AmbiguousName(kind, query, names) => {
+                format!(
+                    "{} {} {}\n{} {}",
+                    constants::AMBIGUOUS_NAME_ERROR.red(),
+                    kind.red(),
+                    query.red().bold(),
+                    "Candidates:".yellow(),
+                    names.join(", ").yellow().bold()
+                )
+            }
+            ArgumentError::LooseMatch(kind, query, name) => {
+                format!(
+                    "{} {} {}\n{} {}",
+                    constants::LOOSE_MATCH_ERROR.red(),
+                    kind.red(),
+                    query.red().bold(),
+                    "Did you mean:".yellow(),
+                    name.yellow().bold()
+                )
+            }
+            ArgumentError::TaskNeedsProject => {
+                format!("{}", constants::TASK_NEEDS_PROJECT_ERROR.red())
+            }
+            ArgumentError::TaskNotInProject(task, task_project, project) => {
+                format!(
+                    "{} {}: {} {}\n{} {}",
+                    "Task".red(),
+                    task.red().bold(),
+                    constants::TASK_NOT_IN_PROJECT_ERROR.red(),
+                    task_project.red().bold(),
+                    "Selected project:".yellow(),
+                    project.yellow().bold()
+                )
+            }
+            ArgumentError::ActionNeedsTimeEntry(action) => {
+                format!(
+                    "{}: {}",
+                    constants::ACTION_NEEDS_TIME_ENTRY_ERROR.red(),
+                    action.red().bold()
+                )
+            }
+            ArgumentError::InvalidProfileName(name) => {
+                format!(
+                    "{}: {}",
+                    constants::INVALID_PROFILE_NAME_ERROR.red(),
+                    name.red().bold()
+                )
+            }
+            ArgumentError::AllWorkspacesWithWorkspace(workspace) => {
+                format!(
+                    "{}: {}",
+                    constants::ALL_WORKSPACES_CONFLICT_ERROR.red(),
+                    workspace.red().bold()
+                )
+            }
         };
         writeln!(f, "{summary}")
     }
 }
 
 impl Error for ArgumentError {}
+
+#[derive(Debug)]
+pub enum NotFoundError {
+    TimeEntry(Option<i64>),
+    Project(String),
+    Task(String),
+    Tag(String),
+}
+
+impl Display for NotFoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let summary = match self {
+            NotFoundError::TimeEntry(Some(id)) => {
+                format!("{} {}", "No time entry found with id".red(), id)
+            }
+            NotFoundError::TimeEntry(None) => format!("{}", "No matching time entry found".red()),
+            NotFoundError::Project(name) => {
+                format!("{} '{}'", "No project found with name".red(), name.bold())
+            }
+            NotFoundError::Task(name) => {
+                format!("{} '{}'", "No task found with name".red(), name.bold())
+            }
+            NotFoundError::Tag(name) => {
+                format!("{} '{}'", "No tag found with name".red(), name.bold())
+            }
+        };
+        writeln!(f, "{summary}")
+    }
+}
+
+impl Error for NotFoundError {}
+
+/// A time entry buffer from the editor that couldn't be read back. Kept free
+/// of colours since the message is also written into the buffer.
+#[derive(Debug)]
+pub enum ParcelError {
+    Line(usize, String),
+    Encoding(String),
+}
+
+impl Display for ParcelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParcelError::Line(line, message) => writeln!(f, "line {line}: {message}"),
+            ParcelError::Encoding(reason) => {
+                writeln!(f, "edited buffer is not valid UTF-8: {reason}")
+            }
+        }
+    }
+}
+
+impl Error for ParcelError {}
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    Read(PathBuf, String),
+    Write(PathBuf, String),
+    UnsupportedVersion(u32),
+}
+
+impl Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let summary = match self {
+            ArchiveError::Read(path, reason) => {
+                format!(
+                    "{}: {}\n{}",
+                    constants::ARCHIVE_READ_ERROR.red(),
+                    path.display(),
+                    reason.yellow()
+                )
+            }
+            ArchiveError::Write(path, reason) => {
+                format!(
+                    "{}: {}\n{}",
+                    constants::ARCHIVE_WRITE_ERROR.red(),
+                    path.display(),
+                    reason.yellow()
+                )
+            }
+            ArchiveError::UnsupportedVersion(version) => {
+                format!(
+                    "{}: {}\n{} {}",
+                    constants::ARCHIVE_UNSUPPORTED_VERSION_ERROR.red(),
+                    version,
+                    constants::OUTDATED_APP_ERROR_MESSAGE.blue().bold(),
+                    constants::ISSUE_LINK.blue().bold().underline()
+                )
+            }
+        };
+        writeln!(f, "{summary}")
+    }
+}
+
+impl Error for ArchiveError {}
+
+#[derive(Debug)]
+pub enum JournalError {
+    Read(PathBuf, String),
+    Write(PathBuf, String),
+    NothingToUndo,
+    MissingState(String),
+}
+
+impl Display for JournalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let summary = match self {
+            JournalError::Read(path, reason) => {
+                format!(
+                    "{}: {}\n{}",
+                    constants::JOURNAL_READ_ERROR.red(),
+                    path.display(),
+                    reason.yellow()
+                )
+            }
+            JournalError::Write(path, reason) => {
+                format!(
+                    "{}: {}\n{}",
+                    constants::JOURNAL_WRITE_ERROR.red(),
+                    path.display(),
+                    reason.yellow()
+                )
+            }
+            JournalError::NothingToUndo => {
+                format!("{}", constants::JOURNAL_NOTHING_TO_UNDO_ERROR.yellow())
+            }
+            JournalError::MissingState(operation) => {
+                format!(
+                    "{} {}",
+                    constants::JOURNAL_MISSING_STATE_ERROR.red(),
+                    operation.bold()
+                )
+            }
+        };
+        writeln!(f, "{summary}")
+    }
+}
+
+impl Error for JournalError {}
+
+/// Broad class of an error. Every class maps to its own process exit code so
+/// scripts can tell a rejected token from a network failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Generic,
+    Validation,
+    Auth,
+    Network,
+    Server,
+    NotFound,
+    Config,
+    Cancelled,
+}
+
+impl ErrorKind {
+    pub fn of(error: &(dyn Error + Send + 'static)) -> ErrorKind {
+        if let Some(error) = error.downcast_ref::<ApiError>() {
+            return match error {
+                ApiError::Network => ErrorKind::Network,
+                ApiError::Unauthorized => ErrorKind::Auth,
+                ApiError::NotFound => ErrorKind::NotFound,
+                ApiError::Forbidden => ErrorKind::Generic,
+                ApiError::Status(_) => ErrorKind::Server,
+                ApiError::Deserialization => ErrorKind::Generic,
+            };
+        }
+        if let Some(error) = error.downcast_ref::<StorageError>() {
+            return match error {
+                StorageError::UnknownBackend(_) => ErrorKind::Config,
+                _ => ErrorKind::Auth,
+            };
+        }
+        if let Some(error) = error.downcast_ref::<PickerError>() {
+            return match error {
+                PickerError::Cancelled => ErrorKind::Cancelled,
+                _ => ErrorKind::Generic,
+            };
+        }
+        if error.downcast_ref::<ConfigError>().is_some() {
+            return ErrorKind::Config;
+        }
+        if let Some(error) = error.downcast_ref::<ArgumentError>() {
+            return match error {
+                ArgumentError::WorkspaceNotFound(_) => ErrorKind::NotFound,
+                _ => ErrorKind::Validation,
+            };
+        }
+        if error.downcast_ref::<structopt::clap::Error>().is_some() {
+            return ErrorKind::Validation;
+        }
+        if error.downcast_ref::<ParcelError>().is_some() {
+            return ErrorKind::Validation;
+        }
+        if error.downcast_ref::<NotFoundError>().is_some() {
+            return ErrorKind::NotFound;
+        }
+        if let Some(ArchiveError::UnsupportedVersion(_)) = error.downcast_ref::<ArchiveError>() {
+            return ErrorKind::Validation;
+        }
+        if let Some(JournalError::NothingToUndo) = error.downcast_ref::<JournalError>() {
+            return ErrorKind::NotFound;
+        }
+        ErrorKind::Generic
+    }
+
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            ErrorKind::Generic => 1,
+            ErrorKind::Validation => 2,
+            ErrorKind::Auth => 3,
+            ErrorKind::Network | ErrorKind::Server => 4,
+            ErrorKind::NotFound => 5,
+            ErrorKind::Config => 6,
+            ErrorKind::Cancelled => 130,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::Generic => "generic",
+            ErrorKind::Validation => "validation",
+            ErrorKind::Auth => "auth",
+            ErrorKind::Network => "network",
+            ErrorKind::Server => "server",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Config => "config",
+            ErrorKind::Cancelled => "cancelled",
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Name matching for flags such as `start --project`. Candidates are ranked in
+//! tiers and only the best non-empty tier is returned, so an exact name always
+//! wins over a name that merely contains the query.
+
+/// Returns the candidates that best match `query`: exact names, then names
+/// equal ignoring case, then names starting with the query, then names
+/// containing it, then names containing its characters in order (`"wbst"`
+/// matches `"Website"`).
+pub fn best_matches<'a, T>(
+    candidates: impl IntoIterator<Item = &'a T>,
+    query: &str,
+    name: impl Fn(&T) -> &str,
+) -> Vec<&'a T> {
+    let query_lowercase = query.to_lowercase();
+    let mut tiers: [Vec<&'a T>; 5] = Default::default();
+    for candidate in candidates {
+        let candidate_name = name(candidate);
+        let candidate_lowercase = candidate_name.to_lowercase();
+        let tier = if candidate_name == query {
+            0
+        } else if candidate_lowercase == query_lowercase {
+            1
+        } else if candidate_lowercase.starts_with(&query_lowercase) {
+            2
+        } else if candidate_lowercase.contains(&query_lowercase) {
+            3
+        } else if is_subsequence(&query_lowercase, &candidate_lowercase) {
+            4
+        } else {
+            continue;
+        };
+        tiers[tier].push(candidate);
+    }
+    tiers
+        .into_iter()
+        .find(|tier| !tier.is_empty())
+        .unwrap_or_default()
+}
+
+/// Whether `name` is close enough to `query` to be used without asking: the
+/// same name ignoring case, or one starting with the query.
+pub fn is_close_match(query: &str, name: &str) -> bool {
+    name.to_lowercase().starts_with(&query.to_lowercase())
+}
+
+fn is_subsequence(query: &str, candidate: &str) -> bool {
+    let mut candidate_chars = candidate.chars();
+    query
+        .chars()
+        .all(|query_char| candidate_chars.any(|c| c == query_char))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names<'a>(matches: Vec<&&'a str>) -> Vec<&'a str> {
+        matches.into_iter().copied().collect()
+    }
+
+    #[test]
+    fn the_best_tier_wins() {
+        let candidates = ["Website", "website redesign", "Web", "Backend"];
+
+        assert_eq!(names(best_matches(&candidates, "Web", |c| c)), vec!["Web"]);
+        assert_eq!(
+            names(best_matches(&candidates, "website", |c| c)),
+            vec!["Website"]
+        );
+        assert_eq!(
+            names(best_matches(&candidates, "redes", |c| c)),
+            vec!["website redesign"]
+        );
+        assert_eq!(
+            names(best_matches(&candidates, "bknd", |c| c)),
+            vec!["Backend"]
+        );
+        assert!(best_matches(&candidates, "mobile", |c| c).is_empty());
+    }
+
+    #[test]
+    fn a_prefix_beats_a_name_containing_the_query() {
+        let candidates = ["Cobweb Archive", "Webshop"];
+
+        assert_eq!(
+            names(best_matches(&candidates, "web", |c| c)),
+            vec!["Webshop"]
+        );
+        assert!(is_close_match("web", "Webshop"));
+        assert!(!is_close_match("web", "Cobweb Archive"));
+    }
+}
@@ -0,0 +1,397 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::error::JournalError;
+use crate::models::{Client, Project, ResultWithDefaultError, Tag, Task, TimeEntry};
+
+/// A change made through the API, with the state needed to revert it. The
+/// previous state is `None` when the entity was never fetched before it was
+/// changed; such operations are listed by `toggl history` but can't be undone.
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Operation {
+    CreateTimeEntry {
+        after: TimeEntry,
+    },
+    UpdateTimeEntry {
+        before: Option<TimeEntry>,
+        after: TimeEntry,
+    },
+    DeleteTimeEntry {
+        workspace_id: i64,
+        id: i64,
+        before: Option<TimeEntry>,
+    },
+    CreateProject {
+        after: Project,
+    },
+    RenameProject {
+        before: Option<Project>,
+        after: Project,
+    },
+    DeleteProject {
+        workspace_id: i64,
+        id: i64,
+        before: Option<Project>,
+    },
+    CreateTag {
+        after: Tag,
+    },
+    RenameTag {
+        before: Option<Tag>,
+        after: Tag,
+    },
+    DeleteTag {
+        workspace_id: i64,
+        id: i64,
+        before: Option<Tag>,
+    },
+    CreateClient {
+        after: Client,
+    },
+    CreateTask {
+        after: Task,
+    },
+}
+
+impl Operation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::CreateTimeEntry { .. } => "create_time_entry",
+            Operation::UpdateTimeEntry { .. } => "update_time_entry",
+            Operation::DeleteTimeEntry { .. } => "delete_time_entry",
+            Operation::CreateProject { .. } => "create_project",
+            Operation::RenameProject { .. } => "rename_project",
+            Operation::DeleteProject { .. } => "delete_project",
+            Operation::CreateTag { .. } => "create_tag",
+            Operation::RenameTag { .. } => "rename_tag",
+            Operation::DeleteTag { .. } => "delete_tag",
+            Operation::CreateClient { .. } => "create_client",
+            Operation::CreateTask { .. } => "create_task",
+        }
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::CreateTimeEntry { after } => {
+                write!(
+                    f,
+                    "Created time entry {} \"{}\"",
+                    after.id,
+                    after.get_description()
+                )
+            }
+            Operation::UpdateTimeEntry { after, .. } => {
+                write!(
+                    f,
+                    "Updated time entry {} \"{}\"",
+                    after.id,
+                    after.get_description()
+                )
+            }
+            Operation::DeleteTimeEntry { id, before, .. } => match before {
+                Some(before) => write!(
+                    f,
+                    "Deleted time entry {id} \"{}\"",
+                    before.get_description()
+                ),
+                None => write!(f, "Deleted time entry {id}"),
+            },
+            Operation::CreateProject { after } => {
+                write!(f, "Created project {} \"{}\"", after.id, after.name)
+            }
+            Operation::RenameProject { before, after } => match before {
+                Some(before) => write!(
+                    f,
+                    "Renamed project {} \"{}\" to \"{}\"",
+                    after.id, before.name, after.name
+                ),
+                None => write!(f, "Renamed project {} to \"{}\"", after.id, after.name),
+            },
+            Operation::DeleteProject { id, before, .. } => match before {
+                Some(before) => write!(f, "Deleted project {id} \"{}\"", before.name),
+                None => write!(f, "Deleted project {id}"),
+            },
+            Operation::CreateTag { after } => {
+                write!(f, "Created tag {} \"{}\"", after.id, after.name)
+            }
+            Operation::RenameTag { before, after } => match before {
+                Some(before) => write!(
+                    f,
+                    "Renamed tag {} \"{}\" to \"{}\"",
+                    after.id, before.name, after.name
+                ),
+                None => write!(f, "Renamed tag {} to \"{}\"", after.id, after.name),
+            },
+            Operation::DeleteTag { id, before, .. } => match before {
+                Some(before) => write!(f, "Deleted tag {id} \"{}\"", before.name),
+                None => write!(f, "Deleted tag {id}"),
+            },
+            Operation::CreateClient { after } => {
+                write!(f, "Created client {} \"{}\"", after.id, after.name)
+            }
+            Operation::CreateTask { after } => write!(
+                f,
+                "Created task {} \"{}\" in project \"{}\"",
+                after.id, after.name, after.project.name
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub at: DateTime<Utc>,
+    pub operation: Operation,
+    #[serde(default)]
+    pub undone: bool,
+}
+
+/// The local record of mutations made by `toggl`, one JSON object per line.
+/// Recording appends a line, and entries older than the retention are only
+/// dropped when the journal is opened. Every access locks the file, so
+/// concurrent runs don't lose each other's entries.
+pub struct Journal {
+    path: PathBuf,
+    retention: Duration,
+}
+
+/// Just the id of a journal line, to number the next entry without parsing
+/// the whole operation.
+#[derive(Deserialize)]
+struct EntryId {
+    id: u64,
+}
+
+impl Journal {
+    pub fn new(path: PathBuf, retention_days: i64) -> Journal {
+        Journal {
+            path,
+            retention: Duration::days(retention_days),
+        }
+    }
+
+    /// The journal at `path`, with expired entries dropped from the file.
+    pub fn open(path: PathBuf, retention_days: i64) -> Journal {
+        let journal = Journal::new(path, retention_days);
+        if journal.path.exists() {
+            // Expired entries are never read, so failing to drop them only
+            // costs disk space
+            let _ = journal.rewrite(|_| {});
+        }
+        journal
+    }
+
+    pub fn path_for_profile(profile: &str) -> PathBuf {
+        config::locate::get_config_root()
+            .join("journal")
+            .join(format!("{profile}.jsonl"))
+    }
+
+    /// Returns the unexpired entries, oldest first.
+    pub fn read(&self) -> ResultWithDefaultError<Vec<JournalEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut file = File::open(&self.path).map_err(|e| read_error(&self.path, e))?;
+        file.lock_shared().map_err(|e| read_error(&self.path, e))?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .map_err(|e| read_error(&self.path, e))?;
+        self.parse(&contents)
+    }
+
+    fn parse(&self, contents: &str) -> ResultWithDefaultError<Vec<JournalEntry>> {
+        let cutoff = Utc::now() - self.retention;
+        contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str::<JournalEntry>(line).map_err(|e| read_error(&self.path, e))
+            })
+            .filter(|entry| entry.as_ref().map_or(true, |entry| entry.at >= cutoff))
+            .collect()
+    }
+
+    pub fn record(&self, operation: Operation) -> ResultWithDefaultError<()> {
+        let mut file = self.open_locked()?;
+        let id = last_id(&mut file).map_err(|e| read_error(&self.path, e))? + 1;
+        let entry = JournalEntry {
+            id,
+            at: Utc::now(),
+            operation,
+            undone: false,
+        };
+        let line = serde_json::to_string(&entry).expect("failed to serialize journal entry") + "\n";
+        file.seek(SeekFrom::End(0))
+            .and_then(|_| file.write_all(line.as_bytes()))
+            .map_err(|e| write_error(&self.path, e))
+    }
+
+    pub fn mark_undone(&self, id: u64) -> ResultWithDefaultError<()> {
+        self.rewrite(|entries| {
+            for entry in entries.iter_mut().filter(|entry| entry.id == id) {
+                entry.undone = true;
+            }
+        })
+    }
+
+    /// Opens the journal for writing, creating it if needed, and locks it
+    /// until the returned file is dropped.
+    fn open_locked(&self) -> ResultWithDefaultError<File> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory).map_err(|e| write_error(&self.path, e))?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)
+            .map_err(|e| write_error(&self.path, e))?;
+        file.lock().map_err(|e| write_error(&self.path, e))?;
+        Ok(file)
+    }
+
+    /// Replaces the unexpired entries with what `change` makes of them.
+    fn rewrite(&self, change: impl FnOnce(&mut Vec<JournalEntry>)) -> ResultWithDefaultError<()> {
+        let mut file = self.open_locked()?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .map_err(|e| read_error(&self.path, e))?;
+        let mut entries = self.parse(&contents)?;
+        change(&mut entries);
+        let contents = entries
+            .iter()
+            .map(|entry| {
+                serde_json::to_string(entry).expect("failed to serialize journal entry") + "\n"
+            })
+            .collect::<String>();
+        // The file stays locked, so it is rewritten in place rather than replaced
+        file.set_len(0)
+            .and_then(|_| file.seek(SeekFrom::Start(0)))
+            .and_then(|_| file.write_all(contents.as_bytes()))
+            .map_err(|e| write_error(&self.path, e))
+    }
+}
+
+/// The id of the last entry in `file`, or 0 when it is empty. Only the end of
+/// the file is read, so recording doesn't slow down as the journal grows.
+fn last_id(file: &mut File) -> std::io::Result<u64> {
+    const CHUNK: u64 = 4096;
+    let mut offset = file.seek(SeekFrom::End(0))?;
+    let mut tail: Vec<u8> = Vec::new();
+    loop {
+        let start = offset.saturating_sub(CHUNK);
+        let mut chunk = vec![0; (offset - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut chunk)?;
+        chunk.extend(tail);
+        tail = chunk;
+        offset = start;
+
+        let text = String::from_utf8_lossy(&tail);
+        let text = text.trim_end();
+        let last_line = match text.rfind('\n') {
+            Some(newline) => &text[newline + 1..],
+            None if offset == 0 => text,
+            None => continue,
+        };
+        if last_line.is_empty() {
+            return Ok(0);
+        }
+        return serde_json::from_str::<EntryId>(last_line)
+            .map(|entry| entry.id)
+            .map_err(std::io::Error::other);
+    }
+}
+
+fn read_error(path: &Path, error: impl std::fmt::Display) -> Box<dyn std::error::Error + Send> {
+    Box::new(JournalError::Read(path.to_path_buf(), error.to_string()))
+}
+
+fn write_error(path: &Path, error: impl std::fmt::Display) -> Box<dyn std::error::Error + Send> {
+    Box::new(JournalError::Write(path.to_path_buf(), error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(id: i64, name: &str) -> Tag {
+        Tag {
+            id,
+            name: name.to_string(),
+            workspace_id: 1,
+        }
+    }
+
+    #[test]
+    fn entries_are_numbered_and_expired_ones_are_dropped() {
+        let directory = tempfile::tempdir().unwrap();
+        let journal = Journal::new(directory.path().join("default.jsonl"), 30);
+        journal
+            .record(Operation::CreateTag {
+                after: tag(1, "old"),
+            })
+            .unwrap();
+        journal
+            .record(Operation::CreateTag {
+                after: tag(2, "new"),
+            })
+            .unwrap();
+
+        let mut entries = journal.read().unwrap();
+        assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), [1, 2]);
+
+        entries[0].at = Utc::now() - Duration::days(31);
+        let contents = entries
+            .iter()
+            .map(|entry| serde_json::to_string(entry).unwrap() + "\n")
+            .collect::<String>();
+        fs::write(directory.path().join("default.jsonl"), contents).unwrap();
+        let journal = Journal::open(directory.path().join("default.jsonl"), 30);
+        journal.mark_undone(2).unwrap();
+
+        let entries = journal.read().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, 2);
+        assert!(entries[0].undone);
+    }
+
+    #[test]
+    fn recording_appends_after_the_last_entry() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("default.jsonl");
+        let journal = Journal::new(path.clone(), 30);
+        // Long enough for the last line to span several chunks
+        let long_name = "x".repeat(10_000);
+        for id in 1..=3 {
+            journal
+                .record(Operation::CreateTag {
+                    after: tag(id, &long_name),
+                })
+                .unwrap();
+        }
+        let before = fs::read_to_string(&path).unwrap();
+        journal
+            .record(Operation::CreateTag {
+                after: tag(4, "new"),
+            })
+            .unwrap();
+
+        let after = fs::read_to_string(&path).unwrap();
+        assert!(after.starts_with(&before));
+        let ids: Vec<u64> = journal.read().unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(ids, [1, 2, 3, 4]);
+    }
+}
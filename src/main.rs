@@ -1,18 +1,26 @@
 mod api;
+mod archive;
 mod arguments;
 mod commands;
 mod config;
 mod constants;
 mod credentials;
 mod error;
+mod fuzzy;
+mod journal;
 mod models;
+mod output;
 mod parcel;
 mod picker;
 mod utilities;
+mod workspace;
 
-use api::client::ApiClient;
 use api::client::V9ApiClient;
+use api::journaled::JournaledApiClient;
+use arguments::AuthSubCommand;
 use arguments::Command::Auth;
+use arguments::Command::Backup;
+use arguments::Command::Browse;
 use arguments::Command::Config;
 use arguments::Command::Continue;
 use arguments::Command::CreateProject;
@@ -22,16 +30,22 @@ use arguments::Command::Delete;
 use arguments::Command::DeleteProject;
 use arguments::Command::DeleteTag;
 use arguments::Command::Edit;
+use arguments::Command::History;
 use arguments::Command::List;
 use arguments::Command::Logout;
 use arguments::Command::RenameProject;
 use arguments::Command::RenameTag;
+use arguments::Command::Restore;
 use arguments::Command::Running;
 use arguments::Command::Start;
 use arguments::Command::Stop;
+use arguments::Command::Undo;
 use arguments::CommandLineArguments;
 use arguments::ConfigSubCommand;
-use commands::auth::AuthenticationCommand;
+use commands::auth::{AuthenticationCommand, AuthenticationStatusCommand};
+use commands::backup::BackupCommand;
+use commands::browse::BrowseCommand;
+use commands::bulk_edit::BulkEditCommand;
 use commands::cont::ContinueCommand;
 use commands::create_project::CreateProjectCommand;
 use commands::create_tag::CreateTagCommand;
@@ -39,37 +53,83 @@ use commands::delete::DeleteCommand;
 use commands::delete_project::DeleteProjectCommand;
 use commands::delete_tag::DeleteTagCommand;
 use commands::edit::EditCommand;
+use commands::history::HistoryCommand;
 use commands::list::ListCommand;
 use commands::rename_project::RenameProjectCommand;
 use commands::rename_tag::RenameTagCommand;
+use commands::restore::RestoreCommand;
 use commands::running::RunningTimeEntryCommand;
 use commands::start::StartCommand;
 use commands::stop::{StopCommand, StopCommandOrigin};
+use commands::undo::UndoCommand;
+use config::global::GlobalConfig;
+use config::profile::ProfileConfig;
 use credentials::get_storage;
 use credentials::Credentials;
+use error::ErrorKind;
+use journal::Journal;
 use models::ResultWithDefaultError;
+use picker::usage::{RankedPicker, UsageStore};
+use picker::ItemPicker;
 use std::io;
+use std::process::ExitCode;
 use structopt::StructOpt;
 
 #[tokio::main]
-async fn main() -> ResultWithDefaultError<()> {
-    let parsed_args = CommandLineArguments::from_args();
-    match execute_subcommand(parsed_args).await {
-        Ok(()) => Ok(()),
-        Err(error) => {
-            // We are catching the error and pretty printing it instead of letting the
-            // program error. Since we are not meant to be used other programs, I think
-            // it's fine to always return a 0 error code, but we might wanna revisit this.
-            print!("{error}");
-            Ok(())
-        }
+async fn main() -> ExitCode {
+    let matches = match CommandLineArguments::clap().get_matches_safe() {
+        Ok(matches) => matches,
+        // --help and --version come back as errors as well
+        Err(error) if !error.use_stderr() => error.exit(),
+        Err(error) => return report_error(Box::new(error), json_errors_requested()),
+    };
+    let parsed_args = CommandLineArguments::from_clap(&matches);
+    let json_errors = parsed_args.error_format == "json" || parsed_args.json;
+    // A global flag counts where it was given, before or after the subcommand
+    let workspace_flag_given = matches.occurrences_of("workspace") > 0
+        || matches
+            .subcommand()
+            .1
+            .is_some_and(|subcommand| subcommand.occurrences_of("workspace") > 0);
+    match execute_subcommand(parsed_args, workspace_flag_given).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => report_error(error, json_errors),
+    }
+}
+
+fn report_error(error: Box<dyn std::error::Error + Send>, json_errors: bool) -> ExitCode {
+    let kind = ErrorKind::of(error.as_ref());
+    if json_errors {
+        colored::control::set_override(false);
+        let report = serde_json::json!({
+            "code": kind.exit_code(),
+            "kind": kind.name(),
+            "message": utilities::strip_ansi(error.to_string().trim()),
+        });
+        eprintln!("{report}");
+    } else {
+        eprintln!("{}", error.to_string().trim_end());
     }
+    ExitCode::from(kind.exit_code())
+}
+
+/// Whether errors should be JSON when the arguments couldn't be parsed and
+/// `--error-format` and `--json` have to be looked for by hand.
+fn json_errors_requested() -> bool {
+    let args: Vec<String> = std::env::args().collect();
+    std::env::var("TOGGL_ERROR_FORMAT").is_ok_and(|format| format == "json")
+        || args.iter().any(|arg| arg == "--json" || arg == "-j")
+        || args.iter().any(|arg| arg == "--error-format=json")
+        || args
+            .windows(2)
+            .any(|pair| pair[0] == "--error-format" && pair[1] == "json")
 }
 
-async fn execute_subcommand(args: CommandLineArguments) -> ResultWithDefaultError<()> {
+async fn execute_subcommand(
+    args: CommandLineArguments,
+    workspace_flag_given: bool,
+) -> ResultWithDefaultError<()> {
     let command = args.cmd;
-    let get_default_api_client = || get_api_client(args.proxy.clone());
-    let picker = picker::get_picker(args.fzf);
     if let Some(directory) = args.directory {
         if !directory.exists() {
             return Err(Box::new(error::ArgumentError::DirectoryNotFound(directory)));
@@ -79,29 +139,122 @@ async fn execute_subcommand(args: CommandLineArguments) -> ResultWithDefaultErro
         }
         std::env::set_current_dir(directory).expect("Couldn't set current directory");
     }
+    output::set_dry_run(args.dry_run);
+    let profile_name = config::profile::resolve_profile_name(args.profile)?;
+    let profile = match (config::profile::get_profile_config(&profile_name), &command) {
+        // Broken profiles must not stand in the way of fixing them
+        (Err(_), Some(Config { .. })) => ProfileConfig::default(),
+        (profile, _) => profile?,
+    };
+    let global_config = match (config::global::get_global_config(), &command) {
+        // Nor must a broken global config
+        (Err(_), Some(Config { .. })) => GlobalConfig::default(),
+        (global_config, _) => global_config?,
+    };
+    let no_color_env = ["NO_COLOR", "CLICOLOR_FORCE"]
+        .iter()
+        .any(|variable| std::env::var_os(variable).is_some());
+    if let (Some(color), false) = (global_config.color(), no_color_env) {
+        colored::control::set_override(color);
+    }
+    config::global::install(global_config.clone());
+    let proxy = args.proxy.clone().or(global_config.proxy.clone());
+    let retention_days = profile.journal_retention_days();
+    let journal = || Journal::open(Journal::path_for_profile(&profile_name), retention_days);
+    let get_default_api_client = || {
+        // Dry runs change nothing, so there is nothing to journal
+        let journal = (!args.dry_run && retention_days > 0).then(journal);
+        get_api_client(&profile_name, &profile, proxy.clone())
+            .map(|client| JournaledApiClient::new(client.with_dry_run(args.dry_run), journal))
+    };
+    let picker_name = if args.fzf {
+        Some("fzf".to_string())
+    } else {
+        args.picker
+            .clone()
+            .or(profile.picker.clone())
+            .or(global_config.picker.clone())
+    };
+    let mut picker_commands = global_config.picker_commands.clone();
+    picker_commands.extend(profile.picker_commands.clone());
+    let picker: Box<dyn ItemPicker> = Box::new(RankedPicker::new(
+        picker::get_picker(picker_name.as_deref(), &picker_commands),
+        UsageStore::new(UsageStore::path_for_profile(&profile_name)),
+    ));
+    // Flags and TOGGL_WORKSPACE pick a workspace to look in, the configs only
+    // pick the one new things are created in
+    let default_workspace = config::active::directory_workspace()
+        .or(profile.workspace.clone())
+        .or(global_config.workspace.clone());
+    let workspace = args.workspace.clone().or(default_workspace.clone());
+    let json = args.json || profile.json.unwrap_or(false);
+    // Nothing is deleted in a dry run, so there is nothing to confirm
+    let yes = args.yes || args.dry_run;
     match command {
-        None => RunningTimeEntryCommand::execute(get_default_api_client()?).await?,
+        None => RunningTimeEntryCommand::execute(get_default_api_client()?, json).await?,
         Some(subcommand) => match subcommand {
             Stop => {
-                StopCommand::execute(&get_default_api_client()?, StopCommandOrigin::CommandLine)
-                    .await?;
+                StopCommand::execute(
+                    &get_default_api_client()?,
+                    StopCommandOrigin::CommandLine,
+                    json,
+                )
+                .await?;
             }
 
-            Continue { interactive } => {
+            Continue {
+                interactive,
+                id,
+                search,
+                description,
+                project,
+                tags,
+                billable,
+                no_billable,
+            } => {
                 let picker = if interactive { Some(picker) } else { None };
-                ContinueCommand::execute(get_default_api_client()?, picker).await?
+                // Without either flag the entry stays as billable as it was
+                let billable = match (billable, no_billable) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                };
+                ContinueCommand::execute(
+                    get_default_api_client()?,
+                    args.workspace,
+                    picker,
+                    id,
+                    search,
+                    description,
+                    project,
+                    tags,
+                    billable,
+                    json,
+                )
+                .await?
             }
 
             List {
                 number,
-                json,
                 since,
                 until,
+                all_workspaces,
                 entity,
             } => {
+                // TOGGL_WORKSPACE is a default rather than a request, so only
+                // the flag conflicts
+                if let (true, true, Some(workspace)) =
+                    (all_workspaces, workspace_flag_given, &args.workspace)
+                {
+                    return Err(Box::new(error::ArgumentError::AllWorkspacesWithWorkspace(
+                        workspace.clone(),
+                    )));
+                }
                 ListCommand::execute(
                     get_default_api_client()?,
-                    number,
+                    args.workspace,
+                    all_workspaces,
+                    number.or(global_config.list_count),
                     json,
                     since,
                     until,
@@ -111,7 +264,7 @@ async fn execute_subcommand(args: CommandLineArguments) -> ResultWithDefaultErro
             }
 
             Current | Running => {
-                RunningTimeEntryCommand::execute(get_default_api_client()?).await?
+                RunningTimeEntryCommand::execute(get_default_api_client()?, json).await?
             }
 
             Start {
@@ -121,67 +274,140 @@ async fn execute_subcommand(args: CommandLineArguments) -> ResultWithDefaultErro
                 project,
                 tags,
                 task,
+                create_project,
+                create_task,
+                create_tags,
             } => {
                 StartCommand::execute(
                     get_default_api_client()?,
                     picker,
+                    args.workspace,
+                    default_workspace,
                     description,
                     project,
                     tags,
                     billable,
                     interactive,
                     task,
+                    create_project,
+                    create_task,
+                    create_tags,
+                    yes,
+                    json,
                 )
                 .await?
             }
 
             CreateProject { name, color } => {
-                CreateProjectCommand::execute(get_default_api_client()?, name, color).await?
+                CreateProjectCommand::execute(
+                    get_default_api_client()?,
+                    workspace,
+                    name,
+                    color,
+                    json,
+                )
+                .await?
             }
 
             DeleteProject { name } => {
-                DeleteProjectCommand::execute(get_default_api_client()?, name).await?
+                DeleteProjectCommand::execute(get_default_api_client()?, workspace, name, yes, json)
+                    .await?
             }
 
             RenameProject { old_name, new_name } => {
-                RenameProjectCommand::execute(get_default_api_client()?, old_name, new_name).await?
+                RenameProjectCommand::execute(
+                    get_default_api_client()?,
+                    workspace,
+                    old_name,
+                    new_name,
+                    json,
+                )
+                .await?
             }
 
             CreateTag { name } => {
-                CreateTagCommand::execute(get_default_api_client()?, name).await?
+                CreateTagCommand::execute(get_default_api_client()?, workspace, name, json).await?
             }
 
             DeleteTag { name } => {
-                DeleteTagCommand::execute(get_default_api_client()?, name).await?
+                DeleteTagCommand::execute(get_default_api_client()?, workspace, name, yes, json)
+                    .await?
             }
 
             RenameTag { old_name, new_name } => {
-                RenameTagCommand::execute(get_default_api_client()?, old_name, new_name).await?
+                RenameTagCommand::execute(
+                    get_default_api_client()?,
+                    workspace,
+                    old_name,
+                    new_name,
+                    json,
+                )
+                .await?
+            }
+
+            Edit { since, until, .. } if since.is_some() || until.is_some() => {
+                BulkEditCommand::execute(
+                    get_default_api_client()?,
+                    args.workspace,
+                    default_workspace,
+                    since,
+                    until,
+                    yes,
+                    json,
+                )
+                .await?
             }
 
             Edit {
                 id,
+                interactive,
                 description,
                 project,
                 tags,
+                pick_tags,
                 start_time,
                 stop_time,
+                ..
             } => {
                 EditCommand::execute(
                     get_default_api_client()?,
                     id,
+                    picker,
+                    interactive,
                     description,
                     project,
                     tags,
+                    pick_tags,
                     start_time,
                     stop_time,
+                    json,
                 )
                 .await?
             }
 
-            Delete { id } => DeleteCommand::execute(get_default_api_client()?, id).await?,
+            Delete { id, interactive } => {
+                // Without an id the entries are always picked from a list
+                let id = if interactive { None } else { id };
+                DeleteCommand::execute(get_default_api_client()?, id, picker, yes, json).await?
+            }
+
+            Auth {
+                cmd: Some(AuthSubCommand::Status),
+                ..
+            } => {
+                AuthenticationStatusCommand::execute(
+                    io::stdout(),
+                    get_storage(&profile_name, &profile)?,
+                    |credentials| V9ApiClient::from_credentials(credentials, proxy),
+                    json,
+                )
+                .await?
+            }
 
-            Auth { api_token } => {
+            Auth {
+                api_token,
+                cmd: None,
+            } => {
                 let api_token = match api_token {
                     Some(token) => token,
                     None => match rpassword::prompt_password("API token: ") {
@@ -190,16 +416,57 @@ async fn execute_subcommand(args: CommandLineArguments) -> ResultWithDefaultErro
                     },
                 };
                 let credentials = Credentials { api_token };
-                let api_client = V9ApiClient::from_credentials(credentials, args.proxy)?;
-                AuthenticationCommand::execute(io::stdout(), api_client, get_storage()).await?
+                let api_client = V9ApiClient::from_credentials(credentials, proxy)?;
+                AuthenticationCommand::execute(
+                    io::stdout(),
+                    api_client,
+                    get_storage(&profile_name, &profile)?,
+                )
+                .await?
             }
 
             Logout => {
-                let storage = get_storage();
+                let storage = get_storage(&profile_name, &profile)?;
                 storage.clear()?;
                 println!("Successfully logged out.");
             }
 
+            Backup { file, since } => {
+                BackupCommand::execute(get_default_api_client()?, file, since, json).await?
+            }
+
+            Restore { file } => {
+                RestoreCommand::execute(
+                    get_default_api_client()?,
+                    file,
+                    workspace,
+                    args.dry_run,
+                    json,
+                )
+                .await?
+            }
+
+            Undo { count } => {
+                // Inverse calls are not journaled themselves, the entries are marked undone
+                let api_client = get_api_client(&profile_name, &profile, proxy.clone())?
+                    .with_dry_run(args.dry_run);
+                UndoCommand::execute(api_client, journal(), count, args.dry_run, yes, json).await?
+            }
+
+            History { number } => HistoryCommand::execute(journal(), number, json).await?,
+
+            Browse => {
+                BrowseCommand::execute(
+                    get_default_api_client()?,
+                    picker,
+                    args.workspace,
+                    default_workspace,
+                    yes,
+                    json,
+                )
+                .await?
+            }
+
             Config {
                 delete,
                 cmd,
@@ -213,6 +480,12 @@ async fn execute_subcommand(args: CommandLineArguments) -> ResultWithDefaultErro
                     ConfigSubCommand::Active => {
                         config::active::ConfigActiveCommand::execute().await?;
                     }
+                    ConfigSubCommand::Profile { edit } => {
+                        config::profile::ConfigProfileCommand::execute(&profile_name, edit).await?;
+                    }
+                    ConfigSubCommand::Global { edit, validate } => {
+                        config::global::ConfigGlobalCommand::execute(edit, validate).await?;
+                    }
                 },
                 None => config::manage::ConfigManageCommand::execute(delete, edit, path).await?,
             },
@@ -222,8 +495,12 @@ async fn execute_subcommand(args: CommandLineArguments) -> ResultWithDefaultErro
     Ok(())
 }
 
-fn get_api_client(proxy: Option<String>) -> ResultWithDefaultError<impl ApiClient> {
-    let credentials_storage = get_storage();
+fn get_api_client(
+    profile_name: &str,
+    profile: &ProfileConfig,
+    proxy: Option<String>,
+) -> ResultWithDefaultError<V9ApiClient> {
+    let credentials_storage = get_storage(profile_name, profile)?;
     match credentials_storage.read() {
         Ok(credentials) => V9ApiClient::from_credentials(credentials, proxy),
         Err(err) => Err(err),
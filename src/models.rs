@@ -1,9 +1,14 @@
 use std::{cmp, env};
 
-use crate::{constants, parcel::Parcel};
+use crate::{
+    config, constants,
+    error::{ArgumentError, ParcelError},
+    parcel::Parcel,
+};
 use std::collections::HashMap;
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use colored::{ColoredString, Colorize};
 use colors_transform::{Color, Rgb};
 use lazy_static::lazy_static;
@@ -24,7 +29,7 @@ impl std::fmt::Display for Tag {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Entities {
     pub time_entries: Vec<TimeEntry>,
     pub projects: HashMap<i64, Project>,
@@ -39,11 +44,44 @@ impl Entities {
         self.time_entries.iter().find(|te| te.is_running()).cloned()
     }
 
-    pub fn workspace_id_for_name(&self, name: &str) -> Option<i64> {
-        self.workspaces
-            .iter()
-            .find(|w| w.name == name)
-            .map(|w| w.id)
+    pub fn workspace_id_for_name(&self, name: &str) -> ResultWithDefaultError<i64> {
+        let matches: Vec<&Workspace> = self.workspaces.iter().filter(|w| w.name == name).collect();
+        match matches.as_slice() {
+            [] => Err(Box::new(ArgumentError::WorkspaceNotFound(name.to_string()))),
+            [workspace] => Ok(workspace.id),
+            _ => Err(Box::new(ArgumentError::AmbiguousWorkspace(
+                name.to_string(),
+                matches.iter().map(|w| w.id).collect(),
+            ))),
+        }
+    }
+
+    /// Resolves a workspace given either by id or by name.
+    pub fn resolve_workspace(&self, name_or_id: &str) -> ResultWithDefaultError<i64> {
+        if let Ok(id) = name_or_id.parse::<i64>() {
+            if self.workspaces.iter().any(|w| w.id == id) {
+                return Ok(id);
+            }
+        }
+        self.workspace_id_for_name(name_or_id)
+    }
+
+    /// Returns a copy with only the time entries, projects, tasks, clients and
+    /// tags that belong to the given workspace.
+    pub fn in_workspace(&self, workspace_id: i64) -> Entities {
+        let mut entities = self.clone();
+        entities
+            .time_entries
+            .retain(|te| te.workspace_id == workspace_id);
+        entities
+            .projects
+            .retain(|_, p| p.workspace_id == workspace_id);
+        entities.tasks.retain(|_, t| t.workspace_id == workspace_id);
+        entities
+            .clients
+            .retain(|_, c| c.workspace_id == workspace_id);
+        entities.tags.retain(|t| t.workspace_id == workspace_id);
+        entities
     }
 
     pub fn project_for_name(&self, workspace_id: i64, name: &str) -> Option<Project> {
@@ -70,6 +108,8 @@ pub struct User {
     pub fullname: Option<String>,
     pub timezone: String,
     pub default_workspace_id: i64,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -258,14 +298,9 @@ impl TimeEntry {
         }
     }
 
-    pub fn get_duration_hmmss(&self) -> String {
-        let duration = self.get_duration();
-        format!(
-            "{}:{:02}:{:02}",
-            duration.num_hours(),
-            duration.num_minutes() % 60,
-            duration.num_seconds() % 60
-        )
+    /// The duration in the configured `duration_format`.
+    pub fn get_display_duration(&self) -> String {
+        config::global::format_duration(self.get_duration())
     }
 
     pub fn is_running(&self) -> bool {
@@ -329,9 +364,9 @@ impl std::fmt::Display for TimeEntry {
                 " ".to_string()
             },
             if self.is_running() {
-                self.get_duration_hmmss().green().bold()
+                self.get_display_duration().green().bold()
             } else {
-                self.get_duration_hmmss().normal()
+                self.get_display_duration().normal()
             },
             if self.is_running() { "*" } else { " " },
             self.get_description().replace('\n', " "),
@@ -397,110 +432,420 @@ const PARCEL_BILLABLE: &str = "Billable";
 const PARCEL_TAGS: &str = "Tags";
 const PARCEL_PROJECT: &str = "Project";
 const PARCEL_TASK: &str = "Task";
+const PARCEL_KEYS: [&str; 7] = [
+    PARCEL_DESCRIPTION,
+    PARCEL_START,
+    PARCEL_STOP,
+    PARCEL_BILLABLE,
+    PARCEL_TAGS,
+    PARCEL_PROJECT,
+    PARCEL_TASK,
+];
+const PARCEL_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const PARCEL_COMMENT_WIDTH: usize = 78;
+
+/// What an editor buffer needs besides the entries themselves: the user's
+/// timezone, in which timestamps are shown, and the projects, tasks and tags
+/// that names typed into the buffer are checked against.
+pub struct EditorContext {
+    pub timezone: Tz,
+    pub entities: Entities,
+}
+
+impl EditorContext {
+    fn format_time(&self, time: DateTime<Utc>) -> String {
+        time.with_timezone(&self.timezone)
+            .format(PARCEL_TIME_FORMAT)
+            .to_string()
+    }
+
+    /// Accepts the buffer's own local format, with or without seconds, as well
+    /// as RFC3339 timestamps carrying an explicit offset.
+    fn parse_time(
+        &self,
+        field: &str,
+        value: &str,
+        line_no: usize,
+    ) -> ResultWithDefaultError<DateTime<Utc>> {
+        if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+            return Ok(time.with_timezone(&Utc));
+        }
+        NaiveDateTime::parse_from_str(value, PARCEL_TIME_FORMAT)
+            .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M"))
+            .ok()
+            .and_then(|local| self.timezone.from_local_datetime(&local).earliest())
+            .map(|time| time.with_timezone(&Utc))
+            .ok_or_else(|| {
+                parcel_error(
+                    line_no,
+                    format!("invalid {field} \"{value}\", expected YYYY-MM-DD HH:MM:SS"),
+                )
+            })
+    }
+
+    /// Comment lines listing the projects, tasks and tags of the workspaces.
+    fn help_comments(&self, workspace_ids: &[i64]) -> String {
+        let mut projects: Vec<String> = self
+            .entities
+            .projects
+            .values()
+            .filter(|p| workspace_ids.contains(&p.workspace_id))
+            .map(|p| p.name.clone())
+            .collect();
+        let mut tasks: Vec<String> = self
+            .entities
+            .tasks
+            .values()
+            .filter(|t| workspace_ids.contains(&t.workspace_id))
+            .map(|t| format!("{} ({})", t.name, t.project.name))
+            .collect();
+        let mut tags: Vec<String> = self
+            .entities
+            .tags
+            .iter()
+            .filter(|t| workspace_ids.contains(&t.workspace_id))
+            .map(|t| t.name.clone())
+            .collect();
+        projects.sort_unstable();
+        tasks.sort_unstable();
+        tags.sort_unstable();
+
+        format!(
+            "# Times are in {}\n{}{}{}",
+            self.timezone.name(),
+            comment_list("Projects", &projects),
+            comment_list("Tasks", &tasks),
+            comment_list("Tags", &tags)
+        )
+    }
+
+    fn has_tag(&self, workspace_id: i64, name: &str) -> bool {
+        self.entities
+            .tags
+            .iter()
+            .any(|t| t.workspace_id == workspace_id && t.name == name)
+    }
+}
+
+fn comment_list(title: &str, names: &[String]) -> String {
+    if names.is_empty() {
+        return format!("# {title}: none\n");
+    }
+    let mut out = String::new();
+    let mut line = format!("# {title}:");
+    for (index, name) in names.iter().enumerate() {
+        let separator = if index + 1 < names.len() { "," } else { "" };
+        let item = format!(" {name}{separator}");
+        if index > 0 && line.len() + item.len() > PARCEL_COMMENT_WIDTH {
+            out.push_str(&line);
+            out.push('\n');
+            line = "#  ".to_string();
+        }
+        line.push_str(&item);
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+fn parcel_error(line_no: usize, message: String) -> Box<dyn std::error::Error + Send> {
+    Box::new(ParcelError::Line(line_no + 1, message))
+}
+
+fn decode_buffer(data: Vec<u8>) -> ResultWithDefaultError<String> {
+    String::from_utf8(data).map_err(|e| -> Box<dyn std::error::Error + Send> {
+        Box::new(ParcelError::Encoding(e.to_string()))
+    })
+}
+
+fn is_comment(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+fn serialize_fields(entry: &TimeEntry, context: &EditorContext) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("{PARCEL_DESCRIPTION}: {}\n", entry.description));
+    out.push_str(&format!(
+        "{PARCEL_START}: {}\n",
+        context.format_time(entry.start)
+    ));
+    if let Some(stop) = entry.stop {
+        out.push_str(&format!("{PARCEL_STOP}: {}\n", context.format_time(stop)));
+    }
+    out.push_str(&format!("{PARCEL_BILLABLE}: {}\n", entry.billable));
+    out.push_str(&format!("{PARCEL_TAGS}: {}\n", entry.tags.join(", ")));
+    out.push_str(&format!(
+        "{PARCEL_PROJECT}: {}\n",
+        entry
+            .project
+            .as_ref()
+            .map(|p| p.name.as_str())
+            .unwrap_or("")
+    ));
+    out.push_str(&format!(
+        "{PARCEL_TASK}: {}\n",
+        entry.task.as_ref().map(|t| t.name.as_str()).unwrap_or("")
+    ));
+    out
+}
+
+/// Lines of an editor buffer together with their (0-based) line numbers.
+type BufferLines<'a> = Vec<(usize, &'a str)>;
+
+/// The entry's current project or task when the buffer still names it. An
+/// archived project or a deleted tag isn't listed among the entities, but an
+/// entry that keeps it must still be saveable.
+fn unchanged<T: Clone>(
+    current: &Option<T>,
+    name: impl Fn(&T) -> &String,
+    value: &str,
+) -> Option<T> {
+    current.as_ref().filter(|item| name(item) == value).cloned()
+}
+
+/// Parses the `Key: value` lines of one entry, given with their line numbers
+/// in the buffer, and resolves project, task and tag names in the entry's
+/// workspace. Names the entry already had are kept even when they are gone
+/// from the workspace.
+fn deserialize_fields(
+    lines: &[(usize, &str)],
+    base: &TimeEntry,
+    context: &EditorContext,
+) -> ResultWithDefaultError<TimeEntry> {
+    let mut entry = TimeEntry {
+        description: String::new(),
+        stop: None,
+        billable: false,
+        tags: Vec::new(),
+        project: None,
+        task: None,
+        ..base.clone()
+    };
+    let mut task: Option<(usize, String)> = None;
+
+    for (line_no, raw) in lines.iter().copied() {
+        let line = raw.trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            return Err(parcel_error(
+                line_no,
+                "expected a \"Key: value\" line".to_string(),
+            ));
+        };
+        let key = key.trim();
+        let value = value.trim();
+        match key {
+            PARCEL_DESCRIPTION => entry.description = value.to_string(),
+            PARCEL_START => entry.start = context.parse_time(PARCEL_START, value, line_no)?,
+            PARCEL_STOP => {
+                entry.stop = if value.is_empty() {
+                    None
+                } else {
+                    Some(context.parse_time(PARCEL_STOP, value, line_no)?)
+                };
+            }
+            PARCEL_BILLABLE => entry.billable = parse_field(PARCEL_BILLABLE, value, line_no)?,
+            PARCEL_TAGS => {
+                entry.tags = value
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                if let Some(unknown) = entry.tags.iter().find(|tag| {
+                    !context.has_tag(entry.workspace_id, tag) && !base.tags.contains(tag)
+                }) {
+                    return Err(parcel_error(line_no, format!("unknown tag \"{unknown}\"")));
+                }
+            }
+            PARCEL_PROJECT => {
+                entry.project = if value.is_empty() {
+                    None
+                } else {
+                    Some(
+                        context
+                            .entities
+                            .project_for_name(entry.workspace_id, value)
+                            .or_else(|| unchanged(&base.project, |p| &p.name, value))
+                            .ok_or_else(|| {
+                                parcel_error(line_no, format!("unknown project \"{value}\""))
+                            })?,
+                    )
+                };
+            }
+            PARCEL_TASK if value.is_empty() => task = None,
+            PARCEL_TASK => task = Some((line_no, value.to_string())),
+            _ => {
+                return Err(parcel_error(
+                    line_no,
+                    format!(
+                        "unknown key \"{key}\", expected one of {}",
+                        PARCEL_KEYS.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+
+    if let Some((line_no, name)) = task {
+        let Some(project) = entry.project.as_ref() else {
+            return Err(parcel_error(
+                line_no,
+                format!("task \"{name}\" needs a project"),
+            ));
+        };
+        entry.task = Some(
+            context
+                .entities
+                .task_for_name(entry.workspace_id, project.id, &name)
+                .or_else(|| {
+                    unchanged(&base.task, |t| &t.name, &name)
+                        .filter(|task| task.project.id == project.id)
+                })
+                .ok_or_else(|| {
+                    parcel_error(
+                        line_no,
+                        format!("unknown task \"{name}\" in project \"{}\"", project.name),
+                    )
+                })?,
+        );
+    }
+
+    entry.duration = match entry.stop {
+        Some(stop) => (stop - entry.start).num_seconds(),
+        None => -entry.start.timestamp(),
+    };
+    Ok(entry)
+}
+
+/// A single time entry opened in the editor by `toggl edit`.
+pub struct EditableTimeEntry<'a> {
+    pub entry: TimeEntry,
+    pub context: &'a EditorContext,
+}
+
+impl Parcel for EditableTimeEntry<'_> {
+    fn serialize(&self) -> Vec<u8> {
+        format!(
+            "{}\n{}",
+            self.context.help_comments(&[self.entry.workspace_id]),
+            serialize_fields(&self.entry, self.context)
+        )
+        .into_bytes()
+    }
+
+    fn deserialize(data: Vec<u8>, base: &Self) -> ResultWithDefaultError<Self> {
+        let text = decode_buffer(data)?;
+        let lines: BufferLines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !is_comment(line))
+            .collect();
+        Ok(EditableTimeEntry {
+            entry: deserialize_fields(&lines, &base.entry, base.context)?,
+            context: base.context,
+        })
+    }
+}
 
-impl Parcel for TimeEntry {
+/// Several time entries edited in one buffer, one block per entry headed by
+/// `## <id>`. Blocks headed `## new` become new entries in `workspace_id`.
+pub struct TimeEntryBatch<'a> {
+    pub entries: Vec<TimeEntry>,
+    pub workspace_id: i64,
+    pub context: &'a EditorContext,
+}
+
+const PARCEL_ENTRY_HEADER: &str = "##";
+const PARCEL_NEW_ENTRY: &str = "new";
+const PARCEL_BATCH_HELP: &str = "\
+# Edit the time entries below, each block starts with a \"## <id>\" header.
+# Remove a block to delete that entry, add a block headed \"## new\" to create one.
+# Lines starting with # are ignored.
+";
+
+impl Parcel for TimeEntryBatch<'_> {
     fn serialize(&self) -> Vec<u8> {
-        let mut out = String::new();
-        out.push_str(&format!("{PARCEL_DESCRIPTION}: {}\n", self.description));
-        out.push_str(&format!("{PARCEL_START}: {}\n", self.start));
-        if let Some(stop) = self.stop {
-            out.push_str(&format!("{PARCEL_STOP}: {stop}\n"));
+        let mut workspace_ids: Vec<i64> = self.entries.iter().map(|te| te.workspace_id).collect();
+        workspace_ids.push(self.workspace_id);
+
+        let mut out = String::from(PARCEL_BATCH_HELP);
+        out.push_str(&self.context.help_comments(&workspace_ids));
+        for entry in self.entries.iter() {
+            out.push_str(&format!("\n{PARCEL_ENTRY_HEADER} {}\n", entry.id));
+            out.push_str(&serialize_fields(entry, self.context));
         }
-        out.push_str(&format!("{PARCEL_BILLABLE}: {}\n", self.billable));
-        out.push_str(&format!("{PARCEL_TAGS}: {}\n", self.tags.join(", ")));
         out.push_str(&format!(
-            "{PARCEL_PROJECT}: {}\n",
-            self.project.as_ref().map(|p| p.name.as_str()).unwrap_or("")
-        ));
-        out.push_str(&format!(
-            "{PARCEL_TASK}: {}\n",
-            self.task.as_ref().map(|t| t.name.as_str()).unwrap_or("")
+            "\n# {PARCEL_ENTRY_HEADER} {PARCEL_NEW_ENTRY}\n# {PARCEL_DESCRIPTION}: \n# {PARCEL_START}: {}\n",
+            self.context.format_time(Utc::now())
         ));
         out.into_bytes()
     }
 
     fn deserialize(data: Vec<u8>, base: &Self) -> ResultWithDefaultError<Self> {
-        let text = String::from_utf8(data).map_err(|e| -> Box<dyn std::error::Error + Send> {
-            Box::new(std::io::Error::other(format!(
-                "edited buffer is not valid UTF-8: {e}"
-            )))
-        })?;
-        let mut entry = TimeEntry {
-            description: String::new(),
-            start: base.start,
-            stop: None,
-            billable: false,
-            tags: Vec::new(),
-            project: None,
-            task: None,
-            id: base.id,
-            duration: base.duration,
-            workspace_id: base.workspace_id,
-            created_with: base.created_with.clone(),
-        };
-        for (line_no, raw) in text.lines().enumerate() {
-            let line = raw.trim_end();
-            if line.is_empty() {
+        let text = decode_buffer(data)?;
+
+        let mut blocks: Vec<(usize, &str, BufferLines)> = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            if let Some(key) = line.strip_prefix(PARCEL_ENTRY_HEADER) {
+                blocks.push((line_no, key.trim(), Vec::new()));
                 continue;
             }
-            let Some((key, value)) = line.split_once(':') else {
+            if is_comment(line) {
                 continue;
-            };
-            let key = key.trim();
-            let value = value.trim();
-            match key {
-                PARCEL_DESCRIPTION => entry.description = value.to_string(),
-                PARCEL_START => entry.start = parse_field(PARCEL_START, value, line_no)?,
-                PARCEL_STOP => {
-                    entry.stop = if value.is_empty() {
-                        None
-                    } else {
-                        Some(parse_field(PARCEL_STOP, value, line_no)?)
-                    };
+            }
+            match blocks.last_mut() {
+                Some((_, _, lines)) => lines.push((line_no, line)),
+                None if line.trim().is_empty() => {}
+                None => {
+                    return Err(parcel_error(
+                        line_no,
+                        format!("expected a \"{PARCEL_ENTRY_HEADER} <id>\" header"),
+                    ));
                 }
-                PARCEL_BILLABLE => entry.billable = parse_field(PARCEL_BILLABLE, value, line_no)?,
-                PARCEL_TAGS => {
-                    entry.tags = if value.is_empty() {
-                        Vec::new()
-                    } else {
-                        value
-                            .split(',')
-                            .map(|s| s.trim().to_string())
-                            .filter(|s| !s.is_empty())
-                            .collect()
-                    };
+            }
+        }
+
+        let mut entries: Vec<TimeEntry> = Vec::new();
+        for (line_no, key, lines) in blocks {
+            let original = if key == PARCEL_NEW_ENTRY {
+                let has_start = lines.iter().any(|(_, line)| {
+                    line.split_once(':')
+                        .is_some_and(|(field, _)| field.trim() == PARCEL_START)
+                });
+                if !has_start {
+                    return Err(parcel_error(
+                        line_no,
+                        format!("a new time entry needs a \"{PARCEL_START}\" line"),
+                    ));
                 }
-                PARCEL_PROJECT => {
-                    entry.project = if value.is_empty() {
-                        None
-                    } else {
-                        Some(Project {
-                            name: value.to_string(),
-                            workspace_id: entry.workspace_id,
-                            ..Project::default()
-                        })
-                    };
+                TimeEntry {
+                    workspace_id: base.workspace_id,
+                    ..TimeEntry::default()
                 }
-                PARCEL_TASK => {
-                    entry.task = if value.is_empty() {
-                        None
-                    } else {
-                        Some(Task {
-                            name: value.to_string(),
-                            workspace_id: entry.workspace_id,
-                            project: entry.project.clone().unwrap_or_default(),
-                            ..Task::default()
-                        })
-                    };
+            } else {
+                let id = parse_field::<i64>("id", key, line_no)?;
+                if entries.iter().any(|te| te.id == id) {
+                    return Err(parcel_error(
+                        line_no,
+                        format!("time entry {id} appears more than once"),
+                    ));
                 }
-                _ => {}
-            }
+                base.entries
+                    .iter()
+                    .find(|te| te.id == id)
+                    .cloned()
+                    .ok_or_else(|| parcel_error(line_no, format!("unknown time entry id {id}")))?
+            };
+            entries.push(deserialize_fields(&lines, &original, base.context)?);
         }
-        entry.duration = match entry.stop {
-            Some(stop) => (stop - entry.start).num_seconds(),
-            None => -entry.start.timestamp(),
-        };
-        Ok(entry)
+
+        Ok(TimeEntryBatch {
+            entries,
+            workspace_id: base.workspace_id,
+            context: base.context,
+        })
     }
 }
 
@@ -514,10 +859,211 @@ where
 {
     value
         .parse::<T>()
-        .map_err(|e| -> Box<dyn std::error::Error + Send> {
-            Box::new(std::io::Error::other(format!(
-                "line {}: invalid {field} \"{value}\": {e}",
-                line_no + 1
-            )))
-        })
+        .map_err(|e| parcel_error(line_no, format!("invalid {field} \"{value}\": {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entities_with_workspaces(workspaces: &[(i64, &str)]) -> Entities {
+        Entities {
+            time_entries: Vec::new(),
+            projects: HashMap::new(),
+            tasks: HashMap::new(),
+            clients: HashMap::new(),
+            workspaces: workspaces
+                .iter()
+                .map(|(id, name)| Workspace {
+                    id: *id,
+                    name: name.to_string(),
+                    admin: false,
+                })
+                .collect(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn a_workspace_is_resolved_by_name_or_id() {
+        let entities = entities_with_workspaces(&[(1, "Personal"), (2, "Acme")]);
+
+        assert_eq!(entities.resolve_workspace("Acme").unwrap(), 2);
+        assert_eq!(entities.resolve_workspace("1").unwrap(), 1);
+    }
+
+    #[test]
+    fn an_ambiguous_or_unknown_workspace_name_is_an_error() {
+        let entities = entities_with_workspaces(&[(1, "Acme"), (2, "Acme")]);
+
+        assert!(entities.resolve_workspace("Acme").is_err());
+        assert!(entities.resolve_workspace("Globex").is_err());
+    }
+
+    fn editor_context() -> EditorContext {
+        let project = Project {
+            id: 7,
+            name: "CLI".to_string(),
+            workspace_id: 1,
+            ..Project::default()
+        };
+        EditorContext {
+            timezone: chrono_tz::Europe::Berlin,
+            entities: Entities {
+                projects: HashMap::from([(project.id, project)]),
+                tags: vec![Tag {
+                    id: 3,
+                    name: "meetings".to_string(),
+                    workspace_id: 1,
+                }],
+                ..Entities::default()
+            },
+        }
+    }
+
+    fn batch_of<'a>(ids: &[i64], context: &'a EditorContext) -> TimeEntryBatch<'a> {
+        TimeEntryBatch {
+            entries: ids
+                .iter()
+                .map(|id| TimeEntry {
+                    id: *id,
+                    description: format!("Entry {id}"),
+                    workspace_id: 1,
+                    ..TimeEntry::default()
+                })
+                .collect(),
+            workspace_id: 1,
+            context,
+        }
+    }
+
+    #[test]
+    fn a_batch_buffer_keeps_edits_drops_removed_blocks_and_adds_new_ones() {
+        let context = editor_context();
+        let batch = batch_of(&[10, 20], &context);
+        let buffer = String::from_utf8(batch.serialize()).unwrap();
+        let buffer = buffer.replace("Entry 10", "Renamed");
+        let start = buffer.find("\n## 20").unwrap();
+        let end = buffer[start + 1..].find("\n#").unwrap() + start + 1;
+        let buffer = format!(
+            "{}{}\n## new\nDescription: Fresh\nStart: 2026-05-11 09:30\nProject: CLI\nTags: meetings\n",
+            &buffer[..start],
+            &buffer[end..]
+        );
+
+        let edited = TimeEntryBatch::deserialize(buffer.into_bytes(), &batch).unwrap();
+
+        let summary: Vec<(i64, &str)> = edited
+            .entries
+            .iter()
+            .map(|te| (te.id, te.description.as_str()))
+            .collect();
+        assert_eq!(summary, vec![(10, "Renamed"), (-1, "Fresh")]);
+        assert_eq!(edited.entries[1].workspace_id, 1);
+        assert_eq!(edited.entries[1].project.as_ref().map(|p| p.id), Some(7));
+    }
+
+    #[test]
+    fn a_batch_buffer_with_an_unknown_id_is_an_error() {
+        let context = editor_context();
+        let batch = batch_of(&[10], &context);
+        let buffer = "## 99\nDescription: Who?\n".to_string();
+
+        assert!(TimeEntryBatch::deserialize(buffer.into_bytes(), &batch).is_err());
+    }
+
+    #[test]
+    fn a_new_block_without_a_start_is_an_error_on_its_header() {
+        let context = editor_context();
+        let batch = batch_of(&[10], &context);
+        let buffer = "## 10\nDescription: Entry 10\n\n## new\nDescription: Fresh\n".to_string();
+
+        let error = TimeEntryBatch::deserialize(buffer.into_bytes(), &batch)
+            .err()
+            .unwrap();
+
+        assert!(matches!(
+            error.downcast_ref::<ParcelError>(),
+            Some(ParcelError::Line(4, _))
+        ));
+    }
+
+    #[test]
+    fn editor_times_are_shown_and_read_in_the_user_timezone() {
+        let context = editor_context();
+        let start = DateTime::parse_from_rfc3339("2026-05-11T07:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let entry = EditableTimeEntry {
+            entry: TimeEntry {
+                start,
+                workspace_id: 1,
+                ..TimeEntry::default()
+            },
+            context: &context,
+        };
+
+        let buffer = String::from_utf8(entry.serialize()).unwrap();
+        assert!(buffer.contains("# Times are in Europe/Berlin"));
+        assert!(buffer.contains("Start: 2026-05-11 09:30:00"));
+
+        let buffer = buffer.replace("09:30:00", "10:00:00");
+        let edited = EditableTimeEntry::deserialize(buffer.into_bytes(), &entry).unwrap();
+        assert_eq!(edited.entry.start, start + Duration::minutes(30));
+    }
+
+    #[test]
+    fn an_entry_keeping_an_archived_project_and_a_deleted_tag_can_be_saved() {
+        let context = editor_context();
+        let archived = Project {
+            id: 8,
+            name: "Legacy".to_string(),
+            workspace_id: 1,
+            active: false,
+            ..Project::default()
+        };
+        let entry = EditableTimeEntry {
+            entry: TimeEntry {
+                workspace_id: 1,
+                project: Some(archived),
+                tags: vec!["retired".to_string()],
+                ..TimeEntry::default()
+            },
+            context: &context,
+        };
+
+        let buffer = String::from_utf8(entry.serialize()).unwrap();
+        let buffer = buffer.replace("Description: ", "Description: Still here");
+        let edited = EditableTimeEntry::deserialize(buffer.into_bytes(), &entry).unwrap();
+        assert_eq!(edited.entry.description, "Still here");
+        assert_eq!(edited.entry.project.map(|p| p.id), Some(8));
+        assert_eq!(edited.entry.tags, vec!["retired"]);
+    }
+
+    #[test]
+    fn unknown_names_and_keys_are_rejected_with_their_line() {
+        let context = editor_context();
+        let entry = EditableTimeEntry {
+            entry: TimeEntry {
+                workspace_id: 1,
+                ..TimeEntry::default()
+            },
+            context: &context,
+        };
+
+        for (buffer, line) in [
+            ("Description: x\nProject: Nope\n", 2),
+            ("# comment\nTags: meetings, nope\n", 2),
+            ("Colour: red\n", 1),
+            ("Project: CLI\nTask: Missing\n", 2),
+        ] {
+            let error = EditableTimeEntry::deserialize(buffer.as_bytes().to_vec(), &entry)
+                .err()
+                .unwrap();
+            match error.downcast_ref::<ParcelError>() {
+                Some(ParcelError::Line(error_line, _)) => assert_eq!(*error_line, line),
+                _ => panic!("expected a line error for {buffer:?}"),
+            }
+        }
+    }
 }
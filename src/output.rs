@@ -0,0 +1,239 @@
+//! Stable JSON shapes printed by `--json`. The models mirror the API and carry
+//! bookkeeping fields such as `Project.at`; these types only expose what
+//! scripts are expected to rely on. See the "JSON output" section of the README
+//! for the schema.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Utc};
+use colored::{ColoredString, Colorize};
+use serde::Serialize;
+
+use crate::journal::JournalEntry;
+use crate::models::{Client, Project, Tag, Task, TimeEntry};
+
+pub trait ToOutput {
+    type Output: Serialize;
+    fn to_output(&self) -> Self::Output;
+}
+
+#[derive(Serialize)]
+pub struct TimeEntryOutput {
+    pub id: i64,
+    pub description: String,
+    pub start: DateTime<Utc>,
+    pub stop: Option<DateTime<Utc>>,
+    pub duration: i64,
+    pub running: bool,
+    pub billable: bool,
+    pub workspace_id: i64,
+    pub project: Option<ProjectOutput>,
+    pub task: Option<TaskOutput>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct ProjectOutput {
+    pub id: i64,
+    pub name: String,
+    pub workspace_id: i64,
+    pub client: Option<ClientOutput>,
+    pub color: String,
+    pub active: bool,
+    pub billable: bool,
+}
+
+#[derive(Serialize)]
+pub struct ClientOutput {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct TaskOutput {
+    pub id: i64,
+    pub name: String,
+    pub project_id: i64,
+}
+
+#[derive(Serialize)]
+pub struct TagOutput {
+    pub id: i64,
+    pub name: String,
+    pub workspace_id: i64,
+}
+
+/// Printed by the delete commands in place of the entity that no longer exists.
+#[derive(Serialize)]
+pub struct DeletedOutput {
+    pub kind: &'static str,
+    pub id: i64,
+    pub deleted: bool,
+}
+
+impl DeletedOutput {
+    pub fn new(kind: &'static str, id: i64) -> DeletedOutput {
+        DeletedOutput {
+            kind,
+            id,
+            deleted: true,
+        }
+    }
+}
+
+impl ToOutput for TimeEntry {
+    type Output = TimeEntryOutput;
+    fn to_output(&self) -> TimeEntryOutput {
+        TimeEntryOutput {
+            id: self.id,
+            description: self.description.clone(),
+            start: self.start,
+            stop: self.stop,
+            duration: self.duration,
+            running: self.is_running(),
+            billable: self.billable,
+            workspace_id: self.workspace_id,
+            project: self.project.as_ref().map(Project::to_output),
+            task: self.task.as_ref().map(Task::to_output),
+            tags: self.tags.clone(),
+        }
+    }
+}
+
+impl ToOutput for Project {
+    type Output = ProjectOutput;
+    fn to_output(&self) -> ProjectOutput {
+        ProjectOutput {
+            id: self.id,
+            name: self.name.clone(),
+            workspace_id: self.workspace_id,
+            client: self.client.as_ref().map(Client::to_output),
+            color: self.color.clone(),
+            active: self.active,
+            billable: self.billable.unwrap_or(false),
+        }
+    }
+}
+
+impl ToOutput for Client {
+    type Output = ClientOutput;
+    fn to_output(&self) -> ClientOutput {
+        ClientOutput {
+            id: self.id,
+            name: self.name.clone(),
+        }
+    }
+}
+
+impl ToOutput for Task {
+    type Output = TaskOutput;
+    fn to_output(&self) -> TaskOutput {
+        TaskOutput {
+            id: self.id,
+            name: self.name.clone(),
+            project_id: self.project.id,
+        }
+    }
+}
+
+impl ToOutput for Tag {
+    type Output = TagOutput;
+    fn to_output(&self) -> TagOutput {
+        TagOutput {
+            id: self.id,
+            name: self.name.clone(),
+            workspace_id: self.workspace_id,
+        }
+    }
+}
+
+/// Printed by `edit --since/--until` once the edited buffer has been applied.
+#[derive(Serialize, Default)]
+pub struct BulkEditOutput {
+    pub updated: Vec<TimeEntryOutput>,
+    pub created: Vec<TimeEntryOutput>,
+    pub deleted: Vec<i64>,
+}
+
+/// One operation of the journal, printed by `history` and `undo`.
+#[derive(Serialize)]
+pub struct HistoryOutput {
+    pub id: u64,
+    pub at: DateTime<Utc>,
+    pub operation: &'static str,
+    pub description: String,
+    pub undone: bool,
+}
+
+impl ToOutput for JournalEntry {
+    type Output = HistoryOutput;
+    fn to_output(&self) -> HistoryOutput {
+        HistoryOutput {
+            id: self.id,
+            at: self.at,
+            operation: self.operation.name(),
+            description: self.operation.to_string(),
+            undone: self.undone,
+        }
+    }
+}
+
+/// Prints a value as pretty JSON on stdout.
+pub fn print_json<T: Serialize + ?Sized>(value: &T) {
+    let json_string = serde_json::to_string_pretty(value).expect("failed to serialize to JSON");
+    println!("{json_string}");
+}
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Marks the run as a dry run, in which changes are only printed and never
+/// reach Toggl.
+pub fn set_dry_run(dry_run: bool) {
+    DRY_RUN.store(dry_run, Ordering::Relaxed);
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Prints what a command changed. A dry run changed nothing and made up the
+/// ids of new entities, so the value is wrapped as
+/// `{"dry_run": true, "would": ...}` for scripts not to take it as a result.
+pub fn print_change_json<T: Serialize + ?Sized>(value: &T) {
+    if is_dry_run() {
+        print_json(&serde_json::json!({ "dry_run": true, "would": value }));
+    } else {
+        print_json(value);
+    }
+}
+
+/// The message reporting a change, `done` normally and
+/// "[dry run] would `would`" in a dry run.
+pub fn change_message(done: &str, would: &str) -> ColoredString {
+    if is_dry_run() {
+        format!("[dry run] would {would}").yellow()
+    } else {
+        done.green()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_entry_output_does_not_expose_project_bookkeeping_fields() {
+        let time_entry = TimeEntry {
+            id: 42,
+            project: Some(Project::default()),
+            ..TimeEntry::default()
+        };
+
+        let json = serde_json::to_value(time_entry.to_output()).unwrap();
+
+        assert_eq!(json["id"], 42);
+        assert!(json["project"].get("at").is_none());
+        assert!(json["project"].get("created_at").is_none());
+        assert!(json.get("created_with").is_none());
+    }
+}
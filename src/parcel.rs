@@ -1,12 +1,14 @@
 use std::fs;
 use std::io;
-use std::io::Write;
 
 use tempfile::Builder;
 
+use crate::error::ParcelError;
 use crate::models::ResultWithDefaultError;
 use crate::utilities;
 
+const PARCEL_ERROR_PREFIX: &str = "# ERROR: ";
+
 fn io_err(context: &str, error: io::Error) -> Box<dyn std::error::Error + Send> {
     Box::new(io::Error::other(format!("{context}: {error}")))
 }
@@ -17,23 +19,74 @@ pub trait Parcel {
     where
         Self: Sized;
 
+    /// Opens the value in the user's editor and reads it back. When a line
+    /// can't be read the buffer is re-opened with the error written below it;
+    /// saving the buffer without changes gives up and returns the error.
     fn update_in_editor(&self) -> ResultWithDefaultError<Self>
     where
         Self: Sized,
     {
-        let mut file = Builder::new()
+        let path = Builder::new()
             .suffix(".txt")
             .tempfile()
-            .map_err(|e| io_err("create tempfile", e))?;
-        file.write_all(&self.serialize())
-            .map_err(|e| io_err("write tempfile", e))?;
-        let path = file.into_temp_path();
+            .map_err(|e| io_err("create tempfile", e))?
+            .into_temp_path();
+        let mut contents = self.serialize();
+
+        loop {
+            fs::write(&path, &contents).map_err(|e| io_err("write tempfile", e))?;
+            utilities::open_path_in_editor(&path)?;
+            let edited = strip_errors(&fs::read(&path).map_err(|e| io_err("read tempfile", e))?);
+
+            let error = match Self::deserialize(edited.clone(), self) {
+                Ok(parcel) => {
+                    path.close().map_err(|e| io_err("close tempfile", e))?;
+                    return Ok(parcel);
+                }
+                Err(error) => error,
+            };
+            match error.downcast_ref::<ParcelError>() {
+                Some(ParcelError::Line(line, message)) if edited != strip_errors(&contents) => {
+                    contents = annotate(&edited, *line, message);
+                }
+                _ => return Err(error),
+            }
+        }
+    }
+}
+
+fn strip_errors(contents: &[u8]) -> Vec<u8> {
+    String::from_utf8_lossy(contents)
+        .lines()
+        .filter(|line| !line.starts_with(PARCEL_ERROR_PREFIX))
+        .map(|line| format!("{line}\n"))
+        .collect::<String>()
+        .into_bytes()
+}
+
+/// Writes the error as a comment right below the (1-based) line it refers to.
+fn annotate(contents: &[u8], line: usize, message: &str) -> Vec<u8> {
+    let text = String::from_utf8_lossy(contents);
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let position = line.min(lines.len());
+    lines.insert(position, format!("{PARCEL_ERROR_PREFIX}{message}"));
+    (lines.join("\n") + "\n").into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        utilities::open_path_in_editor(&path)?;
+    #[test]
+    fn an_error_is_written_below_its_line_and_stripped_on_the_next_read() {
+        let buffer = b"Description: x\nProject: Nope\nTags:\n";
 
-        let contents = fs::read(&path).map_err(|e| io_err("read tempfile", e))?;
-        path.close().map_err(|e| io_err("close tempfile", e))?;
+        let annotated = annotate(buffer, 2, "unknown project \"Nope\"");
 
-        Self::deserialize(contents, self)
+        assert_eq!(
+            String::from_utf8(annotated.clone()).unwrap(),
+            "Description: x\nProject: Nope\n# ERROR: unknown project \"Nope\"\nTags:\n"
+        );
+        assert_eq!(strip_errors(&annotated), buffer.to_vec());
     }
 }
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::Stdio;
+
+use crate::error::PickerError;
+use crate::models::ResultWithDefaultError;
+use crate::utilities;
+
+use super::{ItemPicker, PickableItem, PickableItemKey, PickerAction, Selection};
+
+/// Runs a dmenu-style command through the shell, e.g. `rofi -dmenu -i`: the
+/// items are written to its stdin one per line and it prints the chosen lines.
+/// A printed line that isn't an item is returned as the query. Such commands
+/// can't show marks, so preselected items are listed first instead, nor
+/// colours, so the items are written without them.
+pub struct CommandPicker {
+    command: String,
+}
+
+impl CommandPicker {
+    pub fn new(command: String) -> CommandPicker {
+        CommandPicker { command }
+    }
+
+    /// Feeds `input` to the command and returns what it printed.
+    fn run_command(&self, input: &str) -> ResultWithDefaultError<String> {
+        let failed = |message: String| -> Box<dyn std::error::Error + Send> {
+            Box::new(PickerError::CommandFailed(self.command.clone(), message))
+        };
+        let mut child = utilities::get_shell_cmd(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| failed(e.to_string()))?;
+        // A command that exits without reading everything closes the pipe,
+        // which is fine as long as it printed a choice.
+        let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+        let output = child
+            .wait_with_output()
+            .map_err(|e| failed(e.to_string()))?;
+
+        match output.status.code() {
+            Some(0) => {}
+            // dmenu and rofi exit with 1 when closed without a choice
+            Some(1) => return Err(Box::new(PickerError::Cancelled)),
+            Some(code) => return Err(failed(format!("exit code {code}"))),
+            None => return Err(Box::new(PickerError::Cancelled)),
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn run(
+        &self,
+        mut items: Vec<PickableItem>,
+        preselected: &[PickableItemKey],
+    ) -> ResultWithDefaultError<Selection> {
+        items.sort_by_key(|item| !preselected.contains(&item.key));
+        let possible_elements: HashMap<String, PickableItemKey> = items
+            .iter()
+            .map(|item| (utilities::strip_ansi(&item.formatted), item.key.clone()))
+            .collect();
+        let input: String = items
+            .iter()
+            .map(|item| utilities::strip_ansi(&item.formatted) + "\n")
+            .collect();
+        let output = self.run_command(&input)?;
+
+        let mut keys = Vec::new();
+        let mut query = None;
+        for line in output.lines() {
+            match possible_elements.get(line) {
+                Some(key) => keys.push(key.clone()),
+                None if !line.trim().is_empty() => {
+                    query.get_or_insert(line.trim().to_string());
+                }
+                None => {}
+            }
+        }
+        if keys.is_empty() && query.is_none() {
+            return Err(Box::new(PickerError::Cancelled));
+        }
+        Ok(Selection { keys, query })
+    }
+}
+
+impl ItemPicker for CommandPicker {
+    fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
+        match self.run(items, &[])?.keys.into_iter().next() {
+            None => Err(Box::new(PickerError::Cancelled)),
+            Some(key) => Ok(key),
+        }
+    }
+
+    /// Such commands only report the chosen line, so the action is chosen by
+    /// running the command a second time over the action names.
+    fn pick_with_action(
+        &self,
+        items: Vec<PickableItem>,
+        actions: &[PickerAction],
+    ) -> ResultWithDefaultError<(PickableItemKey, usize)> {
+        let key = self.pick(items)?;
+        if actions.len() < 2 {
+            return Ok((key, 0));
+        }
+        let names: Vec<&str> = actions.iter().map(|action| action.name).collect();
+        let output = self.run_command(&(names.join("\n") + "\n"))?;
+        let chosen = output.lines().next().unwrap_or_default();
+        match names.iter().position(|name| *name == chosen) {
+            Some(action) => Ok((key, action)),
+            None => Err(Box::new(PickerError::Cancelled)),
+        }
+    }
+
+    fn pick_or_type(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<Selection> {
+        self.run(items, &[])
+    }
+
+    fn pick_many(
+        &self,
+        items: Vec<PickableItem>,
+        preselected: &[PickableItemKey],
+    ) -> ResultWithDefaultError<Selection> {
+        self.run(items, preselected)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::models::Tag;
+
+    fn tags() -> Vec<PickableItem> {
+        ["meetings", "reviews"]
+            .iter()
+            .enumerate()
+            .map(|(id, name)| {
+                PickableItem::from_tag(Tag {
+                    id: id as i64,
+                    name: name.to_string(),
+                    workspace_id: 1,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn printed_lines_map_back_to_items_or_become_the_query() {
+        let picked = CommandPicker::new("sed -n 2p".to_string())
+            .pick(tags())
+            .unwrap();
+        assert_eq!(picked.id, 1);
+
+        let typed = CommandPicker::new("cat > /dev/null; echo standup".to_string())
+            .pick_or_type(tags())
+            .unwrap();
+        assert!(typed.keys.is_empty());
+        assert_eq!(typed.query.as_deref(), Some("standup"));
+    }
+
+    #[test]
+    fn items_are_written_without_colours_and_still_map_back() {
+        let mut items = tags();
+        items[1].formatted = "\x1b[32mreviews\x1b[0m".to_string();
+        // Fails the pick if any escape code reaches the command
+        let command = "if grep -q \"$(printf '\\033')\"; then exit 2; fi; echo reviews";
+        let picked = CommandPicker::new(command.to_string()).pick(items).unwrap();
+        assert_eq!(picked.id, 1);
+    }
+}
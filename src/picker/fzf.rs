@@ -1,13 +1,14 @@
 use crate::error;
 use crate::models;
 use crate::picker;
-use crate::utilities;
 use error::PickerError;
 use models::ResultWithDefaultError;
-use picker::{ItemPicker, PickableItem};
+use picker::{actions_header, ItemPicker, PickableItem, PickerAction, Selection};
 use std::collections::HashMap;
+use std::fs;
 use std::io;
 use std::io::Write;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 use super::PickableItemKey;
@@ -28,47 +29,178 @@ fn create_element_hash_map(items: &[PickableItem]) -> HashMap<String, PickableIt
         .collect()
 }
 
-impl ItemPicker for FzfPicker {
-    fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
-        let mut command = Command::new("fzf");
+/// Returns the selection and the index of the action among `actions` it was
+/// accepted with, 0 for enter.
+fn run_fzf(
+    items: Vec<PickableItem>,
+    multi: bool,
+    preselected: &[PickableItemKey],
+    actions: &[PickerAction],
+) -> ResultWithDefaultError<(Selection, usize)> {
+    let mut command = Command::new("fzf");
+    command
+        .arg("--ansi")
+        .arg("--print-query")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped());
+
+    let mut items = items;
+    if multi {
+        // Preselected items are moved to the top and marked one by one once
+        // fzf has started.
+        items.sort_by_key(|item| !preselected.contains(&item.key));
+        let preselected_count = items
+            .iter()
+            .filter(|item| preselected.contains(&item.key))
+            .count();
+        command.arg("--multi");
+        if preselected_count > 0 {
+            command.arg("--bind").arg(format!(
+                "start:{}first",
+                "toggle-out+".repeat(preselected_count)
+            ));
+        }
+    } else {
+        command.arg("-n2..");
+    }
+    if actions.len() > 1 {
+        let keys: Vec<&str> = actions.iter().skip(1).map(|action| action.key).collect();
+        command
+            .arg(format!("--expect={}", keys.join(",")))
+            .arg("--header")
+            .arg(actions_header(actions));
+    }
+
+    // fzf previews run a command, so each preview is written to a file named
+    // after the item's position for that command to print.
+    let preview_directory = if items.iter().any(|item| item.preview.is_some()) {
+        let directory = tempfile::tempdir().map_err(preview_error)?;
+        for (index, item) in items.iter().enumerate() {
+            fs::write(
+                directory.path().join(index.to_string()),
+                item.preview.as_deref().unwrap_or_default(),
+            )
+            .map_err(preview_error)?;
+        }
         command
-            .arg("-n2..")
-            .arg("--ansi")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped());
-
-        match command.spawn() {
-            Ok(mut child) => {
-                let fzf_input = format_as_fzf_input(&items);
-                let possible_elements = create_element_hash_map(&items);
-
-                writeln!(child.stdin.as_mut().unwrap(), "{fzf_input}")
-                    .expect("Failed to write to fzf stdin");
-
-                match child.wait_with_output() {
-                    Err(_) => Err(Box::new(PickerError::Generic)),
-                    Ok(output) => match output.status.code() {
-                        Some(0) => {
-                            let user_selected_string = String::from_utf8(output.stdout).expect(
-                                "Failed to convert fzf output to string. This should never happen.",
-                            );
-                            let selected_item_index =
-                                utilities::remove_trailing_newline(user_selected_string);
-                            let selected_item =
-                                possible_elements.get(&selected_item_index).unwrap();
-                            Ok(selected_item.clone())
+            .arg("--preview")
+            .arg(preview_command(directory.path()))
+            .arg("--preview-window")
+            .arg("right:50%:wrap");
+        Some(directory)
+    } else {
+        None
+    };
+
+    match command.spawn() {
+        Ok(mut child) => {
+            let fzf_input = format_as_fzf_input(&items);
+            let possible_elements = create_element_hash_map(&items);
+
+            writeln!(child.stdin.as_mut().unwrap(), "{fzf_input}")
+                .expect("Failed to write to fzf stdin");
+
+            let output = child.wait_with_output();
+            drop(preview_directory);
+            match output {
+                Err(_) => Err(Box::new(PickerError::Generic)),
+                Ok(output) => match output.status.code() {
+                    // 1 means the query matched nothing
+                    Some(0) | Some(1) => {
+                        let user_selected_string = String::from_utf8(output.stdout).expect(
+                            "Failed to convert fzf output to string. This should never happen.",
+                        );
+                        // --print-query puts the query on the first line and
+                        // --expect the pressed key on the next one, the
+                        // selected items follow one per line
+                        let mut lines = user_selected_string.lines();
+                        let query = lines.next().unwrap_or_default().trim().to_string();
+                        let action = if actions.len() > 1 {
+                            let pressed = lines.next().unwrap_or_default();
+                            actions
+                                .iter()
+                                .skip(1)
+                                .position(|action| action.key == pressed)
+                                .map_or(0, |position| position + 1)
+                        } else {
+                            0
+                        };
+                        let keys = lines
+                            .filter_map(|line| possible_elements.get(line).cloned())
+                            .collect::<Vec<PickableItemKey>>();
+                        let query =
+                            Some(query).filter(|query| keys.is_empty() && !query.is_empty());
+                        if keys.is_empty() && query.is_none() {
+                            return Err(Box::new(PickerError::Cancelled));
                         }
-                        // This is copied from zoxide's fzf handler.
-                        // https://github.com/rohankumardubey/zoxide/blob/main/src/util.rs
-                        Some(128..=254) | None => Err(Box::new(PickerError::Cancelled)),
-                        _ => Err(Box::new(PickerError::Generic)),
-                    },
-                }
+                        Ok((Selection { keys, query }, action))
+                    }
+                    // This is copied from zoxide's fzf handler.
+                    // https://github.com/rohankumardubey/zoxide/blob/main/src/util.rs
+                    Some(128..=254) | None => Err(Box::new(PickerError::Cancelled)),
+                    _ => Err(Box::new(PickerError::Generic)),
+                },
             }
-            Err(e) if e.kind() == io::ErrorKind::NotFound => {
-                Err(Box::new(PickerError::FzfNotInstalled))
-            }
-            Err(_) => Err(Box::new(PickerError::Generic)),
         }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            Err(Box::new(PickerError::FzfNotInstalled))
+        }
+        Err(_) => Err(Box::new(PickerError::Generic)),
+    }
+}
+
+pub fn is_installed() -> bool {
+    Command::new("fzf")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+fn preview_error(_: io::Error) -> Box<dyn std::error::Error + Send> {
+    Box::new(PickerError::Generic)
+}
+
+#[cfg(unix)]
+fn preview_command(directory: &Path) -> String {
+    format!("cat '{}'/{{n}}", directory.display())
+}
+
+#[cfg(not(unix))]
+fn preview_command(directory: &Path) -> String {
+    format!("type \"{}\\{{n}}\"", directory.display())
+}
+
+impl ItemPicker for FzfPicker {
+    fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
+        match run_fzf(items, false, &[], &[])?.0.keys.into_iter().next() {
+            None => Err(Box::new(PickerError::Cancelled)),
+            Some(key) => Ok(key),
+        }
+    }
+
+    fn pick_with_action(
+        &self,
+        items: Vec<PickableItem>,
+        actions: &[PickerAction],
+    ) -> ResultWithDefaultError<(PickableItemKey, usize)> {
+        let (selection, action) = run_fzf(items, false, &[], actions)?;
+        match selection.keys.into_iter().next() {
+            None => Err(Box::new(PickerError::Cancelled)),
+            Some(key) => Ok((key, action)),
+        }
+    }
+
+    fn pick_or_type(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<Selection> {
+        run_fzf(items, false, &[], &[]).map(|(selection, _)| selection)
+    }
+
+    fn pick_many(
+        &self,
+        items: Vec<PickableItem>,
+        preselected: &[PickableItemKey],
+    ) -> ResultWithDefaultError<Selection> {
+        run_fzf(items, true, preselected, &[]).map(|(selection, _)| selection)
     }
 }
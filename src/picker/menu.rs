@@ -0,0 +1,315 @@
+use std::io;
+use std::io::{BufRead, Write};
+
+use colored::Colorize;
+
+use crate::error::PickerError;
+use crate::models::ResultWithDefaultError;
+
+use super::{ItemPicker, PickableItem, PickableItemKey, PickerAction, Selection};
+
+/// Items listed at once; typing text narrows the list down.
+const SHOWN_ITEMS: usize = 30;
+
+/// A numbered list read from plain stdin, for when neither skim nor fzf can
+/// run. The list goes to stderr like the confirmation prompts, so `--json`
+/// output stays parseable.
+pub struct MenuPicker;
+
+enum Mode {
+    /// Only a listed item can be picked.
+    Pick,
+    /// Text that matches no item is returned as the query.
+    PickOrType,
+    /// Numbers mark and unmark items, an empty line confirms.
+    PickMany,
+}
+
+fn matches_filter(item: &PickableItem, filter: &str) -> bool {
+    item.formatted
+        .to_lowercase()
+        .contains(&filter.to_lowercase())
+}
+
+fn parse_numbers(line: &str, count: usize) -> Option<Vec<usize>> {
+    line.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            token
+                .parse::<usize>()
+                .ok()
+                .filter(|number| (1..=count).contains(number))
+        })
+        .collect()
+}
+
+fn run_menu(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    items: Vec<PickableItem>,
+    mode: Mode,
+    preselected: &[PickableItemKey],
+) -> ResultWithDefaultError<Selection> {
+    let cancelled =
+        || -> ResultWithDefaultError<Selection> { Err(Box::new(PickerError::Cancelled)) };
+    let mut marked: Vec<PickableItemKey> = items
+        .iter()
+        .filter(|item| preselected.contains(&item.key))
+        .map(|item| item.key.clone())
+        .collect();
+    let mut filter = String::new();
+    // With nothing listed no filter can ever match, so don't ask
+    if items.is_empty() && matches!(mode, Mode::Pick) {
+        return cancelled();
+    }
+
+    loop {
+        let shown: Vec<&PickableItem> = items
+            .iter()
+            .filter(|item| matches_filter(item, &filter))
+            .collect();
+        if shown.is_empty() {
+            match mode {
+                Mode::Pick => {
+                    let _ = writeln!(output, "{}", format!("Nothing matches \"{filter}\"").red());
+                    filter.clear();
+                    continue;
+                }
+                Mode::PickOrType | Mode::PickMany => {
+                    return Ok(Selection {
+                        keys: marked,
+                        query: Some(filter),
+                    });
+                }
+            }
+        }
+
+        for (number, item) in shown.iter().enumerate().take(SHOWN_ITEMS) {
+            let mark = if marked.contains(&item.key) { "*" } else { " " };
+            let _ = writeln!(output, "{:>3}){mark} {}", number + 1, item.formatted);
+        }
+        if shown.len() > SHOWN_ITEMS {
+            let _ = writeln!(
+                output,
+                "     ... and {} more, type to narrow the list",
+                shown.len() - SHOWN_ITEMS
+            );
+        }
+        let prompt = match mode {
+            Mode::Pick => "Number, or text to filter (empty to cancel): ".to_string(),
+            Mode::PickOrType if filter.is_empty() => {
+                "Number, or text to filter (empty to cancel): ".to_string()
+            }
+            Mode::PickOrType => format!("Number, or empty to use \"{filter}\": "),
+            Mode::PickMany => {
+                "Numbers to mark or unmark, text to filter (empty to confirm): ".to_string()
+            }
+        };
+        let _ = write!(output, "{prompt}");
+        let _ = output.flush();
+
+        let mut line = String::new();
+        if input.read_line(&mut line).unwrap_or(0) == 0 {
+            return cancelled();
+        }
+        let line = line.trim();
+
+        if line.is_empty() {
+            return match mode {
+                Mode::PickOrType if !filter.is_empty() => Ok(Selection {
+                    keys: Vec::new(),
+                    query: Some(filter),
+                }),
+                Mode::PickMany if !marked.is_empty() => Ok(Selection {
+                    keys: marked,
+                    query: None,
+                }),
+                _ => cancelled(),
+            };
+        }
+
+        match (parse_numbers(line, shown.len()), &mode) {
+            (Some(numbers), Mode::PickMany) => {
+                for number in numbers {
+                    let key = &shown[number - 1].key;
+                    match marked.iter().position(|marked_key| marked_key == key) {
+                        Some(position) => {
+                            marked.remove(position);
+                        }
+                        None => marked.push(key.clone()),
+                    }
+                }
+            }
+            (Some(numbers), _) if numbers.len() == 1 => {
+                return Ok(Selection {
+                    keys: vec![shown[numbers[0] - 1].key.clone()],
+                    query: None,
+                });
+            }
+            (Some(_), _) => {
+                let _ = writeln!(output, "{}", "Pick a single number".red());
+            }
+            (None, _) => filter = line.to_string(),
+        }
+    }
+}
+
+/// Asks which of `actions` to perform, the first one being the default.
+fn choose_action(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    actions: &[PickerAction],
+) -> ResultWithDefaultError<usize> {
+    for (number, action) in actions.iter().enumerate() {
+        let _ = writeln!(output, "{:>3}) {}", number + 1, action.name);
+    }
+    loop {
+        let _ = write!(output, "Action (empty to {}): ", actions[0].name);
+        let _ = output.flush();
+        let mut line = String::new();
+        if input.read_line(&mut line).unwrap_or(0) == 0 {
+            return Err(Box::new(PickerError::Cancelled));
+        }
+        match line.trim() {
+            "" => return Ok(0),
+            line => match parse_numbers(line, actions.len()).as_deref() {
+                Some([number]) => return Ok(number - 1),
+                _ => {
+                    let _ = writeln!(output, "{}", "Pick a single number".red());
+                }
+            },
+        }
+    }
+}
+
+fn run_menu_on_stdin(
+    items: Vec<PickableItem>,
+    mode: Mode,
+    preselected: &[PickableItemKey],
+) -> ResultWithDefaultError<Selection> {
+    run_menu(
+        &mut io::stdin().lock(),
+        &mut io::stderr(),
+        items,
+        mode,
+        preselected,
+    )
+}
+
+impl ItemPicker for MenuPicker {
+    fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
+        match run_menu_on_stdin(items, Mode::Pick, &[])?
+            .keys
+            .into_iter()
+            .next()
+        {
+            None => Err(Box::new(PickerError::Cancelled)),
+            Some(key) => Ok(key),
+        }
+    }
+
+    fn pick_with_action(
+        &self,
+        items: Vec<PickableItem>,
+        actions: &[PickerAction],
+    ) -> ResultWithDefaultError<(PickableItemKey, usize)> {
+        let key = self.pick(items)?;
+        if actions.len() < 2 {
+            return Ok((key, 0));
+        }
+        let action = choose_action(&mut io::stdin().lock(), &mut io::stderr(), actions)?;
+        Ok((key, action))
+    }
+
+    fn pick_or_type(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<Selection> {
+        run_menu_on_stdin(items, Mode::PickOrType, &[])
+    }
+
+    fn pick_many(
+        &self,
+        items: Vec<PickableItem>,
+        preselected: &[PickableItemKey],
+    ) -> ResultWithDefaultError<Selection> {
+        run_menu_on_stdin(items, Mode::PickMany, preselected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Tag;
+
+    fn tags() -> Vec<PickableItem> {
+        ["meetings", "deep work", "reviews"]
+            .iter()
+            .enumerate()
+            .map(|(id, name)| {
+                PickableItem::from_tag(Tag {
+                    id: id as i64,
+                    name: name.to_string(),
+                    workspace_id: 1,
+                })
+            })
+            .collect()
+    }
+
+    fn run(input: &str, mode: Mode, preselected: &[PickableItemKey]) -> Selection {
+        run_menu(
+            &mut io::Cursor::new(input),
+            &mut io::sink(),
+            tags(),
+            mode,
+            preselected,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn numbers_pick_from_the_filtered_list_and_other_text_is_kept() {
+        let picked = run("rev\n1\n", Mode::Pick, &[]);
+        assert_eq!(picked.keys[0].id, 2);
+
+        let typed = run("standup\n", Mode::PickOrType, &[]);
+        assert!(typed.keys.is_empty());
+        assert_eq!(typed.query.as_deref(), Some("standup"));
+
+        let preselected = [tags()[0].key.clone()];
+        let marked = run("1 3\n\n", Mode::PickMany, &preselected);
+        assert_eq!(
+            marked.keys.iter().map(|key| key.id).collect::<Vec<i64>>(),
+            [2]
+        );
+    }
+
+    #[test]
+    fn picking_from_an_empty_list_is_cancelled_without_reading_input() {
+        let picked = run_menu(
+            &mut io::Cursor::new("1\n"),
+            &mut io::sink(),
+            Vec::new(),
+            Mode::Pick,
+            &[],
+        );
+        assert!(picked.is_err());
+    }
+
+    #[test]
+    fn an_empty_answer_picks_the_default_action() {
+        let actions = [
+            PickerAction {
+                key: "enter",
+                name: "continue",
+            },
+            PickerAction {
+                key: "ctrl-e",
+                name: "edit",
+            },
+        ];
+        let choose = |input: &str| {
+            choose_action(&mut io::Cursor::new(input), &mut io::sink(), &actions).unwrap()
+        };
+
+        assert_eq!(choose("\n"), 0);
+        assert_eq!(choose("3\n2\n"), 1);
+    }
+}
@@ -1,33 +1,43 @@
+mod command;
 mod fzf;
+mod menu;
+pub mod preview;
 #[cfg(unix)]
 mod skim;
+pub mod usage;
 
 use std::borrow::BorrowMut;
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::io::IsTerminal;
 use std::str::FromStr;
 
+use crate::config;
 use crate::constants;
 use crate::models;
 use crate::models::Project;
+use crate::models::Tag;
 use crate::models::Task;
 use models::{ResultWithDefaultError, TimeEntry};
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct PickableItemKey {
     pub id: i64,
     pub kind: PickableItemKind,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum PickableItemKind {
     TimeEntry,
     Project,
     Task,
+    Tag,
 }
 
 pub struct PickableItem {
     key: PickableItemKey,
     formatted: String,
+    preview: Option<String>,
 }
 
 impl FromStr for PickableItemKey {
@@ -40,6 +50,7 @@ impl FromStr for PickableItemKey {
             "TimeEntry" => PickableItemKind::TimeEntry,
             "Project" => PickableItemKind::Project,
             "Task" => PickableItemKind::Task,
+            "Tag" => PickableItemKind::Tag,
             _ => return Err(()),
         };
 
@@ -61,6 +72,7 @@ impl Display for PickableItemKey {
                 PickableItemKind::TimeEntry => "TimeEntry",
                 PickableItemKind::Project => "Project",
                 PickableItemKind::Task => "Task",
+                PickableItemKind::Tag => "Tag",
             },
             self.id
         )
@@ -68,10 +80,20 @@ impl Display for PickableItemKey {
 }
 
 impl PickableItem {
+    /// Shows `preview` next to the list while the item is highlighted.
+    pub fn with_preview(self, preview: String) -> PickableItem {
+        PickableItem {
+            preview: Some(preview),
+            ..self
+        }
+    }
+
     pub fn from_time_entry(time_entry: TimeEntry) -> PickableItem {
         let formatted_time_entry = format!(
-            "{} {} - {} {}",
+            "{} {} [{}] {} - {} {}",
             if time_entry.billable { "$" } else { " " },
+            config::global::local_time(time_entry.start).format("%Y-%m-%d %H:%M"),
+            time_entry.get_display_duration(),
             time_entry.get_description(),
             match time_entry.project.clone() {
                 Some(p) => p.name,
@@ -86,6 +108,31 @@ impl PickableItem {
                 kind: PickableItemKind::TimeEntry,
             },
             formatted: formatted_time_entry,
+            preview: None,
+        }
+    }
+
+    /// Offers the description of an earlier entry, together with the project
+    /// and tags it would bring along.
+    pub fn from_description(time_entry: TimeEntry) -> PickableItem {
+        let formatted_description = format!(
+            "{} {} - {} {}",
+            if time_entry.billable { "$" } else { " " },
+            time_entry.get_description(),
+            match time_entry.project.clone() {
+                Some(p) => p.name,
+                None => constants::NO_PROJECT.to_string(),
+            },
+            time_entry.get_display_tags()
+        );
+
+        PickableItem {
+            key: PickableItemKey {
+                id: time_entry.id,
+                kind: PickableItemKind::TimeEntry,
+            },
+            formatted: formatted_description,
+            preview: None,
         }
     }
 
@@ -105,6 +152,7 @@ impl PickableItem {
                 kind: PickableItemKind::Project,
             },
             formatted: formatted_project,
+            preview: None,
         }
     }
 
@@ -125,24 +173,144 @@ impl PickableItem {
                 kind: PickableItemKind::Task,
             },
             formatted: formatted_task,
+            preview: None,
+        }
+    }
+
+    pub fn from_tag(tag: Tag) -> PickableItem {
+        PickableItem {
+            key: PickableItemKey {
+                id: tag.id,
+                kind: PickableItemKind::Tag,
+            },
+            formatted: tag.name,
+            preview: None,
         }
     }
 }
 
+/// What a multi-select session returned.
+pub struct Selection {
+    pub keys: Vec<PickableItemKey>,
+    /// The text that was typed, when it matched none of the items.
+    pub query: Option<String>,
+}
+
+/// Something to do with a picked item, chosen by the key it is picked with.
+pub struct PickerAction {
+    /// A key name understood by skim and fzf, such as `ctrl-e`.
+    pub key: &'static str,
+    pub name: &'static str,
+}
+
+/// A one-line legend of the actions, the first one being picked with enter.
+fn actions_header(actions: &[PickerAction]) -> String {
+    actions
+        .iter()
+        .enumerate()
+        .map(|(index, action)| {
+            let key = if index == 0 { "enter" } else { action.key };
+            format!("{key}: {}", action.name)
+        })
+        .collect::<Vec<String>>()
+        .join("  ")
+}
+
 pub trait ItemPicker {
     fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey>;
+    /// Like `pick`, but also returns the index of the action to perform: the
+    /// first one when the item is picked with enter, otherwise the one whose
+    /// key was pressed.
+    fn pick_with_action(
+        &self,
+        items: Vec<PickableItem>,
+        actions: &[PickerAction],
+    ) -> ResultWithDefaultError<(PickableItemKey, usize)>;
+    /// Like `pick`, but returns the typed text instead of failing when it
+    /// matches none of the items.
+    fn pick_or_type(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<Selection>;
+    /// Like `pick`, but lets the user mark several items (tab in skim and fzf),
+    /// starting with `preselected` marked. Confirming without marking anything
+    /// selects the highlighted item.
+    fn pick_many(
+        &self,
+        items: Vec<PickableItem>,
+        preselected: &[PickableItemKey],
+    ) -> ResultWithDefaultError<Selection>;
+}
+
+/// Lets the user choose among the workspace's tags, starting from `current`.
+/// A name that matches no tag can be typed to add it as a new tag. Names in
+/// `current` the workspace doesn't know are kept.
+pub fn pick_tags(
+    picker: &dyn ItemPicker,
+    known_tags: &[Tag],
+    current: &[String],
+) -> ResultWithDefaultError<Vec<String>> {
+    let preselected: Vec<PickableItemKey> = known_tags
+        .iter()
+        .filter(|tag| current.contains(&tag.name))
+        .map(|tag| PickableItemKey {
+            id: tag.id,
+            kind: PickableItemKind::Tag,
+        })
+        .collect();
+    let selection = picker.pick_many(
+        known_tags
+            .iter()
+            .cloned()
+            .map(PickableItem::from_tag)
+            .collect(),
+        &preselected,
+    )?;
+
+    let mut tags: Vec<String> = current
+        .iter()
+        .filter(|name| !known_tags.iter().any(|tag| &tag.name == *name))
+        .cloned()
+        .collect();
+    tags.extend(
+        known_tags
+            .iter()
+            .filter(|tag| selection.keys.iter().any(|key| key.id == tag.id))
+            .map(|tag| tag.name.clone()),
+    );
+    tags.extend(selection.query);
+    Ok(tags)
 }
 
+/// Whether a full-screen picker can take over the terminal.
+fn has_terminal() -> bool {
+    std::io::stdin().is_terminal() && std::env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
+/// Resolves the picker named by `--picker` or the profile: `skim` (the
+/// default), `fzf`, `menu`, a name from `commands`, or else a command line.
+/// Skim and fzf fall back to the numbered menu when there is no terminal for
+/// them or fzf isn't installed.
 #[cfg(unix)]
-pub fn get_picker(force_fzf: bool) -> Box<dyn ItemPicker> {
-    if force_fzf {
-        Box::new(fzf::FzfPicker)
-    } else {
-        Box::new(skim::SkimPicker)
+pub fn get_picker(name: Option<&str>, commands: &HashMap<String, String>) -> Box<dyn ItemPicker> {
+    match name {
+        Some("menu") => Box::new(menu::MenuPicker),
+        Some(name) if name != "skim" && name != "fzf" => Box::new(command::CommandPicker::new(
+            commands.get(name).cloned().unwrap_or(name.to_string()),
+        )),
+        _ if !has_terminal() => Box::new(menu::MenuPicker),
+        Some("fzf") if !fzf::is_installed() => Box::new(menu::MenuPicker),
+        Some("fzf") => Box::new(fzf::FzfPicker),
+        _ => Box::new(skim::SkimPicker),
     }
 }
 
+/// Like the unix version, with fzf standing in for skim.
 #[cfg(not(unix))]
-pub fn get_picker(_force_fzf: bool) -> Box<dyn ItemPicker> {
-    Box::new(fzf::FzfPicker)
+pub fn get_picker(name: Option<&str>, commands: &HashMap<String, String>) -> Box<dyn ItemPicker> {
+    match name {
+        Some("menu") => Box::new(menu::MenuPicker),
+        Some(name) if name != "skim" && name != "fzf" => Box::new(command::CommandPicker::new(
+            commands.get(name).cloned().unwrap_or(name.to_string()),
+        )),
+        _ if has_terminal() && fzf::is_installed() => Box::new(fzf::FzfPicker),
+        _ => Box::new(menu::MenuPicker),
+    }
 }
@@ -0,0 +1,85 @@
+//! Text for the preview pane of the pickers, with the details that don't fit
+//! on an item's line.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::global;
+use crate::constants;
+use crate::models::{Project, TimeEntry};
+
+fn yes_or_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Describes an entry; `history` is searched for its description.
+pub fn time_entry(time_entry: &TimeEntry, history: &[TimeEntry]) -> String {
+    let local_time = |time: DateTime<Utc>| {
+        global::local_time(time)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    };
+    let uses = history
+        .iter()
+        .filter(|te| te.description == time_entry.description)
+        .count();
+
+    let mut lines = vec![
+        time_entry.get_description(),
+        String::new(),
+        format!("Start:     {}", local_time(time_entry.start)),
+        format!(
+            "Stop:      {}",
+            time_entry.stop.map_or("running".to_string(), local_time)
+        ),
+        format!("Duration:  {}", time_entry.get_display_duration()),
+        format!(
+            "Project:   {}",
+            time_entry
+                .project
+                .as_ref()
+                .map_or(constants::NO_PROJECT.to_string(), |p| p.name.clone())
+        ),
+    ];
+    if let Some(client) = time_entry.project.as_ref().and_then(|p| p.client.as_ref()) {
+        lines.push(format!("Client:    {}", client.name));
+    }
+    if let Some(task) = &time_entry.task {
+        lines.push(format!("Task:      {}", task.name));
+    }
+    lines.push(format!("Tags:      {}", time_entry.tags.join(", ")));
+    lines.push(format!("Billable:  {}", yes_or_no(time_entry.billable)));
+    lines.push(String::new());
+    lines.push(format!("Description used {uses} times in history"));
+    lines.join("\n")
+}
+
+/// Describes a project; `history` is searched for the time tracked on it.
+pub fn project(project: &Project, history: &[TimeEntry]) -> String {
+    let week_start = global::start_of_week(Utc::now());
+    let tracked_this_week = history
+        .iter()
+        .filter(|te| {
+            te.start >= week_start && te.project.as_ref().is_some_and(|p| p.id == project.id)
+        })
+        .fold(Duration::zero(), |total, te| total + te.get_duration());
+
+    [
+        project.name.clone(),
+        String::new(),
+        format!(
+            "Client:     {}",
+            project.client.as_ref().map_or("", |c| c.name.as_str())
+        ),
+        format!("Colour:     {}", project.color),
+        format!(
+            "Billable:   {}",
+            project.billable.map_or("not set", yes_or_no)
+        ),
+        format!("This week:  {}", global::format_duration(tracked_this_week)),
+    ]
+    .join("\n")
+}
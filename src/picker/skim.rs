@@ -3,17 +3,41 @@ use crate::models;
 use crate::picker;
 use error::PickerError;
 use models::ResultWithDefaultError;
-use picker::{ItemPicker, PickableItem, PickableItemKey};
+use picker::{actions_header, ItemPicker, PickableItem, PickableItemKey, PickerAction, Selection};
 use skim::prelude::*;
 
 pub struct SkimPicker;
 
-fn get_skim_configuration(items: Vec<PickableItem>) -> (SkimOptions<'static>, SkimItemReceiver) {
+/// Keys that accept the highlighted item for an action, with their legend.
+struct ExpectedKeys {
+    keys: String,
+    header: String,
+}
+
+fn get_skim_configuration<'a>(
+    items: Vec<PickableItem>,
+    multi: bool,
+    preselected: &[PickableItemKey],
+    expected: Option<&'a ExpectedKeys>,
+) -> (SkimOptions<'a>, SkimItemReceiver) {
+    let preset = items
+        .iter()
+        .filter(|item| preselected.contains(&item.key))
+        .map(|item| item.formatted.clone());
+    // An empty preview command makes skim ask the items for their preview
+    let preview = items
+        .iter()
+        .any(|item| item.preview.is_some())
+        .then_some("");
     let options = SkimOptionsBuilder::default()
         // Set viewport to take entire screen
         .height(Some("100%"))
-        // Disable multiselect
-        .multi(false)
+        .multi(multi)
+        .preview(preview)
+        .preview_window(Some("right:50%:wrap"))
+        .expect(expected.map(|expected| expected.keys.clone()))
+        .header(expected.map(|expected| expected.header.as_str()))
+        .selector(Some(Rc::new(DefaultSkimSelector::default().preset(preset))))
         .build()
         .unwrap();
 
@@ -35,33 +59,94 @@ impl SkimItem for PickableItem {
     fn output<'a>(&'a self) -> Cow<'a, str> {
         Cow::from(self.key.to_string())
     }
+
+    fn preview(&self, _context: PreviewContext) -> ItemPreview {
+        ItemPreview::Text(self.preview.clone().unwrap_or_default())
+    }
 }
 
-impl ItemPicker for SkimPicker {
-    fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
-        let (options, source) = get_skim_configuration(items);
-        let output = Skim::run_with(&options, Some(source));
+/// Returns the selection and, when `expected` is given, the expected key it
+/// was accepted with.
+fn run_skim(
+    items: Vec<PickableItem>,
+    multi: bool,
+    preselected: &[PickableItemKey],
+    expected: Option<&ExpectedKeys>,
+) -> ResultWithDefaultError<(Selection, Option<String>)> {
+    let (options, source) = get_skim_configuration(items, multi, preselected, expected);
+    let output = Skim::run_with(&options, Some(source));
+
+    match output {
+        None => Err(Box::new(PickerError::Cancelled)),
+        Some(item) => {
+            if item.is_abort {
+                Err(Box::new(PickerError::Cancelled))
+            } else {
+                let keys = item
+                    .selected_items
+                    .iter()
+                    .map(|selected_items| {
+                        selected_items.output().parse::<PickableItemKey>().unwrap()
+                    })
+                    .collect::<Vec<PickableItemKey>>();
+                // Nothing is selected only when the query matched no item
+                let query = Some(item.query.trim().to_string())
+                    .filter(|query| keys.is_empty() && !query.is_empty());
 
-        match output {
-            None => Err(Box::new(PickerError::Cancelled)),
-            Some(item) => {
-                if item.is_abort {
-                    Err(Box::new(PickerError::Cancelled))
-                } else {
-                    let selectable_items = item
-                        .selected_items
-                        .iter()
-                        .map(|selected_items| {
-                            selected_items.output().parse::<PickableItemKey>().unwrap()
-                        })
-                        .collect::<Vec<PickableItemKey>>();
+                let key = match item.final_event {
+                    Event::EvActAccept(key) => key,
+                    _ => None,
+                };
 
-                    match selectable_items.first() {
-                        None => Err(Box::new(PickerError::Generic)),
-                        Some(id) => Ok(id.clone()),
-                    }
-                }
+                Ok((Selection { keys, query }, key))
             }
         }
     }
 }
+
+impl ItemPicker for SkimPicker {
+    fn pick(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
+        match run_skim(items, false, &[], None)?.0.keys.into_iter().next() {
+            None => Err(Box::new(PickerError::Generic)),
+            Some(key) => Ok(key),
+        }
+    }
+
+    fn pick_with_action(
+        &self,
+        items: Vec<PickableItem>,
+        actions: &[PickerAction],
+    ) -> ResultWithDefaultError<(PickableItemKey, usize)> {
+        let expected = ExpectedKeys {
+            keys: actions
+                .iter()
+                .skip(1)
+                .map(|action| action.key)
+                .collect::<Vec<&str>>()
+                .join(","),
+            header: actions_header(actions),
+        };
+        let (selection, key) = run_skim(items, false, &[], Some(&expected))?;
+        let action = actions
+            .iter()
+            .skip(1)
+            .position(|action| key.as_deref() == Some(action.key))
+            .map_or(0, |position| position + 1);
+        match selection.keys.into_iter().next() {
+            None => Err(Box::new(PickerError::Generic)),
+            Some(key) => Ok((key, action)),
+        }
+    }
+
+    fn pick_or_type(&self, items: Vec<PickableItem>) -> ResultWithDefaultError<Selection> {
+        run_skim(items, false, &[], None).map(|(selection, _)| selection)
+    }
+
+    fn pick_many(
+        &self,
+        items: Vec<PickableItem>,
+        preselected: &[PickableItemKey],
+    ) -> ResultWithDefaultError<Selection> {
+        run_skim(items, true, preselected, None).map(|(selection, _)| selection)
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::models::ResultWithDefaultError;
+
+use super::{ItemPicker, PickableItem, PickableItemKey, PickableItemKind, PickerAction, Selection};
+
+/// Picks that have decayed below this score are forgotten.
+const FORGOTTEN_SCORE: f64 = 0.001;
+/// Picks made in a directory are forgotten after this many days.
+const DIRECTORY_RETENTION_DAYS: i64 = 90;
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct ItemUsage {
+    score: f64,
+    last_used: DateTime<Utc>,
+}
+
+impl ItemUsage {
+    /// Every pick adds one to the score, which halves for every week that
+    /// passes without another pick.
+    fn frecency(&self, now: DateTime<Utc>) -> f64 {
+        let weeks = (now - self.last_used).num_hours().max(0) as f64 / (24.0 * 7.0);
+        self.score * 0.5_f64.powf(weeks)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Usage {
+    #[serde(default)]
+    items: HashMap<String, ItemUsage>,
+    /// When each item was last picked in a directory, by directory.
+    #[serde(default)]
+    directories: HashMap<PathBuf, HashMap<String, DateTime<Utc>>>,
+}
+
+/// Remembers which projects, tasks and tags were picked, and in which
+/// directory, so that pickers can offer the likely choice first. Time entries
+/// keep the order they are listed in.
+pub struct UsageStore {
+    path: PathBuf,
+}
+
+impl UsageStore {
+    pub fn new(path: PathBuf) -> UsageStore {
+        UsageStore { path }
+    }
+
+    pub fn path_for_profile(profile: &str) -> PathBuf {
+        config::locate::get_config_root()
+            .join("usage")
+            .join(format!("{profile}.json"))
+    }
+
+    // Ranking is a convenience, so a store that can't be read or written
+    // only costs the ordering.
+    fn read(&self) -> Usage {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn write(&self, usage: &Usage) -> ResultWithDefaultError<()> {
+        let write = || -> std::io::Result<()> {
+            if let Some(directory) = self.path.parent() {
+                fs::create_dir_all(directory)?;
+            }
+            fs::write(&self.path, serde_json::to_string(usage)?)
+        };
+        write().map_err(|e| -> Box<dyn std::error::Error + Send> { Box::new(e) })
+    }
+
+    /// Orders the items with the one last picked in `directory` first, then
+    /// by frecency, then by name.
+    fn rank(&self, items: &mut [PickableItem], directory: Option<&Path>, now: DateTime<Utc>) {
+        if items
+            .iter()
+            .any(|item| item.key.kind == PickableItemKind::TimeEntry)
+        {
+            return;
+        }
+        let usage = self.read();
+        let picked_here = directory.and_then(|directory| usage.directories.get(directory));
+        let last_picked_here = picked_here.and_then(|picked_here| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let key = item.key.to_string();
+                    picked_here.get(&key).map(|at| (*at, key))
+                })
+                .max()
+                .map(|(_, key)| key)
+        });
+        let frecency = |item: &PickableItem| {
+            usage
+                .items
+                .get(&item.key.to_string())
+                .map_or(0.0, |item_usage| item_usage.frecency(now))
+        };
+
+        items.sort_by(|a, b| {
+            let a_last = last_picked_here.as_ref() == Some(&a.key.to_string());
+            let b_last = last_picked_here.as_ref() == Some(&b.key.to_string());
+            b_last
+                .cmp(&a_last)
+                .then_with(|| frecency(b).total_cmp(&frecency(a)))
+                .then_with(|| a.formatted.cmp(&b.formatted))
+        });
+    }
+
+    fn record(
+        &self,
+        keys: &[PickableItemKey],
+        directory: Option<&Path>,
+        now: DateTime<Utc>,
+    ) -> ResultWithDefaultError<()> {
+        let keys: Vec<String> = keys
+            .iter()
+            .filter(|key| key.kind != PickableItemKind::TimeEntry)
+            .map(PickableItemKey::to_string)
+            .collect();
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let mut usage = self.read();
+        for key in &keys {
+            let score = usage
+                .items
+                .get(key)
+                .map_or(0.0, |item_usage| item_usage.frecency(now));
+            usage.items.insert(
+                key.clone(),
+                ItemUsage {
+                    score: score + 1.0,
+                    last_used: now,
+                },
+            );
+            if let Some(directory) = directory {
+                usage
+                    .directories
+                    .entry(directory.to_path_buf())
+                    .or_default()
+                    .insert(key.clone(), now);
+            }
+        }
+
+        usage
+            .items
+            .retain(|_, item_usage| item_usage.frecency(now) >= FORGOTTEN_SCORE);
+        let cutoff = now - Duration::days(DIRECTORY_RETENTION_DAYS);
+        for picked_here in usage.directories.values_mut() {
+            picked_here.retain(|_, at| *at >= cutoff);
+        }
+        usage
+            .directories
+            .retain(|_, picked_here| !picked_here.is_empty());
+        self.write(&usage)
+    }
+}
+
+/// Ranks the items of the wrapped picker by past picks and remembers what is
+/// picked through it.
+pub struct RankedPicker {
+    inner: Box<dyn ItemPicker>,
+    store: UsageStore,
+}
+
+impl RankedPicker {
+    pub fn new(inner: Box<dyn ItemPicker>, store: UsageStore) -> RankedPicker {
+        RankedPicker { inner, store }
+    }
+
+    fn rank(&self, items: &mut [PickableItem]) {
+        self.store
+            .rank(items, std::env::current_dir().ok().as_deref(), Utc::now());
+    }
+
+    fn record(&self, keys: &[PickableItemKey]) {
+        let _ = self
+            .store
+            .record(keys, std::env::current_dir().ok().as_deref(), Utc::now());
+    }
+}
+
+impl ItemPicker for RankedPicker {
+    fn pick(&self, mut items: Vec<PickableItem>) -> ResultWithDefaultError<PickableItemKey> {
+        self.rank(&mut items);
+        let key = self.inner.pick(items)?;
+        self.record(std::slice::from_ref(&key));
+        Ok(key)
+    }
+
+    fn pick_with_action(
+        &self,
+        mut items: Vec<PickableItem>,
+        actions: &[PickerAction],
+    ) -> ResultWithDefaultError<(PickableItemKey, usize)> {
+        self.rank(&mut items);
+        let (key, action) = self.inner.pick_with_action(items, actions)?;
+        self.record(std::slice::from_ref(&key));
+        Ok((key, action))
+    }
+
+    fn pick_or_type(&self, mut items: Vec<PickableItem>) -> ResultWithDefaultError<Selection> {
+        self.rank(&mut items);
+        let selection = self.inner.pick_or_type(items)?;
+        self.record(&selection.keys);
+        Ok(selection)
+    }
+
+    fn pick_many(
+        &self,
+        mut items: Vec<PickableItem>,
+        preselected: &[PickableItemKey],
+    ) -> ResultWithDefaultError<Selection> {
+        self.rank(&mut items);
+        let selection = self.inner.pick_many(items, preselected)?;
+        self.record(&selection.keys);
+        Ok(selection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(id: i64, name: &str) -> PickableItem {
+        PickableItem {
+            key: PickableItemKey {
+                id,
+                kind: PickableItemKind::Project,
+            },
+            formatted: name.to_string(),
+            preview: None,
+        }
+    }
+
+    fn key(id: i64) -> PickableItemKey {
+        project(id, "").key
+    }
+
+    #[test]
+    fn the_last_pick_in_the_directory_comes_first_then_frecency_then_name() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = UsageStore::new(directory.path().join("default.json"));
+        let here = Path::new("/work/website");
+        let elsewhere = Path::new("/work/backend");
+        let now = Utc::now();
+        store.record(&[key(2)], Some(elsewhere), now).unwrap();
+        store.record(&[key(2)], Some(elsewhere), now).unwrap();
+        store
+            .record(&[key(3)], Some(here), now - Duration::days(1))
+            .unwrap();
+
+        let mut items = vec![
+            project(4, "Docs"),
+            project(3, "Website"),
+            project(1, "Admin"),
+            project(2, "Backend"),
+        ];
+        store.rank(&mut items, Some(here), now);
+
+        assert_eq!(
+            items.iter().map(|item| item.key.id).collect::<Vec<i64>>(),
+            [3, 2, 1, 4]
+        );
+    }
+}
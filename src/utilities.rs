@@ -5,8 +5,33 @@ use std::{
 
 use colored::Colorize;
 use directories::BaseDirs;
+use lazy_static::lazy_static;
+use regex::Regex;
 
-use crate::{constants, models::ResultWithDefaultError};
+use crate::{constants, error::ArgumentError, models::ResultWithDefaultError};
+
+/// Rejects a `--since` or `--until` that isn't a date up front, instead of
+/// sending it to the API unchecked and in a single request.
+pub fn check_date_argument(flag: &str, date: Option<&str>) -> ResultWithDefaultError<()> {
+    match date {
+        Some(date) if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() => {
+            Err(Box::new(ArgumentError::InvalidDate(
+                flag.to_string(),
+                date.to_string(),
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+lazy_static! {
+    static ref ANSI_ESCAPE: Regex = Regex::new("\x1b\\[[0-9;]*m").unwrap();
+}
+
+/// `text` without the escape codes that colour it.
+pub fn strip_ansi(text: &str) -> String {
+    ANSI_ESCAPE.replace_all(text, "").into_owned()
+}
 
 pub fn remove_trailing_newline(value: String) -> String {
     value.trim_end().to_string()
@@ -50,6 +75,45 @@ pub fn read_from_stdin_with_constraints(text: &str, valid_values: &[String]) ->
     }
 }
 
+/// Shows what a destructive command is about to do and asks before going
+/// ahead. `assume_yes` (from `--yes`) skips the prompt; without it a
+/// non-interactive stdin is refused rather than treated as consent. The prompt
+/// goes to stderr so `--json` output stays parseable.
+pub fn confirm(summary: &str, question: &str, assume_yes: bool) -> ResultWithDefaultError<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(Box::new(ArgumentError::ConfirmationRequired));
+    }
+    if !summary.is_empty() {
+        eprintln!("{summary}");
+    }
+    loop {
+        eprint!("{question} (y/N): ");
+        io::stderr().flush().unwrap();
+        let mut answer = String::new();
+        io::stdin()
+            .read_line(&mut answer)
+            .expect("Failed to read line");
+        match answer.trim() {
+            "y" | "Y" | "yes" => return Ok(true),
+            "" | "n" | "N" | "no" => return Ok(false),
+            other => eprintln!("{}", format!("Invalid answer \"{other}\"").red()),
+        }
+    }
+}
+
+/// Asks for a line of text on stderr, like `confirm`, so `--json` output
+/// stays parseable. End of input reads as an empty answer.
+pub fn read_line_from_stderr_prompt(prompt: &str) -> String {
+    eprint!("{prompt}");
+    io::stderr().flush().unwrap();
+    let mut answer = String::new();
+    let _ = io::stdin().read_line(&mut answer);
+    answer.trim().to_string()
+}
+
 pub fn open_path_in_editor<P>(path: P) -> ResultWithDefaultError<()>
 where
     P: AsRef<Path>,
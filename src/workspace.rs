@@ -0,0 +1,17 @@
+use crate::api::client::ApiClient;
+use crate::models::ResultWithDefaultError;
+
+/// Resolves the workspace picked with `--workspace`/`TOGGL_WORKSPACE`, falling
+/// back to the user's default workspace when none was given.
+pub async fn resolve_workspace_id(
+    api_client: &impl ApiClient,
+    workspace: Option<&str>,
+) -> ResultWithDefaultError<i64> {
+    match workspace {
+        Some(name_or_id) => api_client
+            .get_entities()
+            .await?
+            .resolve_workspace(name_or_id),
+        None => Ok(api_client.get_user().await?.default_workspace_id),
+    }
+}